@@ -0,0 +1,365 @@
+use std::fmt;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use super::{AnimatorStateMachine, Ease};
+
+/// How a keyframe's value moves towards the next keyframe.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+pub enum Interpolation {
+    /// Holds the value until the next keyframe.
+    Step,
+    #[default]
+    Linear,
+    Eased(Ease),
+    /// Catmull-Rom spline through the neighbouring keyframes.
+    Smooth,
+}
+
+/// Values a [`Curve`] can interpolate, handled as up to four floats.
+pub trait Keyable: Copy {
+    fn to_vec4(self) -> Vec4;
+    fn from_vec4(value: Vec4) -> Self;
+}
+
+impl Keyable for f32 {
+    fn to_vec4(self) -> Vec4 {
+        Vec4::new(self, 0., 0., 0.)
+    }
+
+    fn from_vec4(value: Vec4) -> Self {
+        value.x
+    }
+}
+
+impl Keyable for (f32, f32) {
+    fn to_vec4(self) -> Vec4 {
+        Vec4::new(self.0, self.1, 0., 0.)
+    }
+
+    fn from_vec4(value: Vec4) -> Self {
+        (value.x, value.y)
+    }
+}
+
+/// Interpolated indices are rounded down, so a linear key from 0 to 3
+/// shows every frame in between.
+impl Keyable for usize {
+    fn to_vec4(self) -> Vec4 {
+        Vec4::new(self as f32, 0., 0., 0.)
+    }
+
+    fn from_vec4(value: Vec4) -> Self {
+        value.x.max(0.).floor() as usize
+    }
+}
+
+/// Colour written as hex, e.g. `"C8AC93"`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct HexColor(pub Color);
+
+impl TryFrom<String> for HexColor {
+    type Error = bevy::render::color::HexColorError;
+
+    fn try_from(hex: String) -> Result<Self, Self::Error> {
+        Color::hex(hex).map(Self)
+    }
+}
+
+impl Keyable for HexColor {
+    fn to_vec4(self) -> Vec4 {
+        Vec4::from_array(self.0.as_rgba_f32())
+    }
+
+    fn from_vec4(value: Vec4) -> Self {
+        Self(Color::rgba(value.x, value.y, value.z, value.w))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Keyframe<V> {
+    /// Seconds since the start of the clip.
+    pub time: f32,
+    pub value: V,
+    #[serde(default)]
+    pub interpolation: Interpolation,
+}
+
+/// Keyframes of a single property, sorted by time.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Curve<V>(pub Vec<Keyframe<V>>);
+
+impl<V> Default for Curve<V> {
+    fn default() -> Self {
+        Self(vec![])
+    }
+}
+
+impl<V> Curve<V> {
+    /// Sorts the keys by time, failing on times that are not finite or
+    /// appear more than once.
+    pub fn sort(&mut self) -> Result<(), KeyframeError> {
+        if let Some(key) = self.0.iter().find(|key| !key.time.is_finite()) {
+            return Err(KeyframeError::NonFinite(key.time));
+        }
+
+        self.0.sort_by(|a, b| a.time.total_cmp(&b.time));
+        match self.0.windows(2).find(|pair| pair[0].time == pair[1].time) {
+            Some(pair) => Err(KeyframeError::Duplicate(pair[0].time)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<V: Keyable> Curve<V> {
+    /// Value at `time`, holding the first and last keys outside the curve.
+    pub fn sample(&self, time: f32) -> Option<V> {
+        let keys = &self.0;
+        let next = keys.iter().position(|key| key.time > time);
+
+        let (i, key) = match next {
+            None => return keys.last().map(|key| key.value),
+            Some(0) => return Some(keys[0].value),
+            Some(next) => (next - 1, &keys[next - 1]),
+        };
+        let to = &keys[i + 1];
+        let t = ((time - key.time) / (to.time - key.time)).clamp(0., 1.);
+
+        let value = match key.interpolation {
+            Interpolation::Step => key.value.to_vec4(),
+            Interpolation::Linear => key.value.to_vec4().lerp(to.value.to_vec4(), t),
+            Interpolation::Eased(ease) => {
+                key.value.to_vec4().lerp(to.value.to_vec4(), ease.apply(t))
+            }
+            Interpolation::Smooth => {
+                let before = keys[i.saturating_sub(1)].value.to_vec4();
+                let after = keys.get(i + 2).unwrap_or(to).value.to_vec4();
+                catmull_rom(before, key.value.to_vec4(), to.value.to_vec4(), after, t)
+            }
+        };
+
+        Some(V::from_vec4(value))
+    }
+}
+
+/// Keyframe times a [`Curve`] cannot be sampled with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyframeError {
+    NonFinite(f32),
+    Duplicate(f32),
+}
+
+impl fmt::Display for KeyframeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyframeError::NonFinite(time) => write!(f, "keyframe time {time} is not finite"),
+            KeyframeError::Duplicate(time) => write!(f, "more than one keyframe at {time}s"),
+        }
+    }
+}
+
+impl std::error::Error for KeyframeError {}
+
+fn catmull_rom(p0: Vec4, p1: Vec4, p2: Vec4, p3: Vec4, t: f32) -> Vec4 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    0.5 * (2. * p1
+        + (p2 - p0) * t
+        + (2. * p0 - 5. * p1 + 4. * p2 - p3) * t2
+        + (3. * p1 - p0 - 3. * p2 + p3) * t3)
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ClipEvent {
+    /// Seconds since the start of the clip.
+    pub time: f32,
+    pub name: String,
+}
+
+/// Keyframed animation authored in a `.anim.ron` file. Properties without
+/// keys are left to the state machine playing the clip.
+#[derive(Debug, Clone, Deserialize, TypeUuid, TypePath)]
+#[uuid = "5d8f3a27-9c1e-4b6a-8e2d-f07b14c9a3e6"]
+pub struct KeyframeClip {
+    pub duration: f32,
+    #[serde(default)]
+    pub looping: bool,
+    #[serde(default)]
+    pub translation: Curve<(f32, f32)>,
+    /// Radians around the z axis.
+    #[serde(default)]
+    pub rotation: Curve<f32>,
+    #[serde(default)]
+    pub scale: Curve<(f32, f32)>,
+    #[serde(default)]
+    pub sprite_index: Curve<usize>,
+    /// Multiplied with the sprite's palette colour.
+    #[serde(default)]
+    pub tint: Curve<HexColor>,
+    /// Sent as [`crate::AnimationEvent`]s when playback passes them.
+    #[serde(default)]
+    pub events: Vec<ClipEvent>,
+}
+
+impl KeyframeClip {
+    /// Sorts the keys of every curve, see [`Curve::sort`].
+    fn sort_keys(&mut self) -> Result<(), KeyframeError> {
+        self.translation.sort()?;
+        self.rotation.sort()?;
+        self.scale.sort()?;
+        self.sprite_index.sort()?;
+        self.tint.sort()
+    }
+
+    pub fn sample(&self, time: f32) -> ClipPose {
+        ClipPose {
+            translation: self.translation.sample(time).map(Vec2::from),
+            rotation: self.rotation.sample(time),
+            scale: self.scale.sample(time).map(Vec2::from),
+            sprite_index: self.sprite_index.sample(time),
+            tint: self.tint.sample(time).map(|tint| tint.0),
+        }
+    }
+}
+
+/// A clip sampled at a point in time, `None` for properties it does not animate.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ClipPose {
+    pub translation: Option<Vec2>,
+    pub rotation: Option<f32>,
+    pub scale: Option<Vec2>,
+    pub sprite_index: Option<usize>,
+    pub tint: Option<Color>,
+}
+
+impl ClipPose {
+    pub fn transform(&self) -> Transform {
+        Transform {
+            translation: self.translation.unwrap_or_default().extend(0.),
+            rotation: Quat::from_rotation_z(self.rotation.unwrap_or_default()),
+            scale: self.scale.unwrap_or(Vec2::ONE).extend(1.),
+        }
+    }
+}
+
+/// Plays a clip by handle, for entities whose animation needs no code.
+/// Returns to the default empty state once a non-looping clip ends.
+#[derive(Debug, Default, Clone, Reflect)]
+pub struct ClipState {
+    pub clip: Handle<KeyframeClip>,
+}
+
+impl AnimatorStateMachine for ClipState {
+    fn calculate_transform(&self, _t: f32) -> Transform {
+        Transform::IDENTITY
+    }
+
+    fn clip(&self, _asset_server: &AssetServer) -> Option<Handle<KeyframeClip>> {
+        Some(self.clip.clone())
+    }
+
+    fn next(&self) -> Option<Self> {
+        None
+    }
+}
+
+#[derive(Default)]
+pub struct KeyframeClipLoader;
+
+impl AssetLoader for KeyframeClipLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut clip = ron::de::from_bytes::<KeyframeClip>(bytes)?;
+            clip.sort_keys()?;
+            load_context.set_default_asset(LoadedAsset::new(clip));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(interpolation: Interpolation, keys: &[(f32, f32)]) -> Curve<f32> {
+        Curve(
+            keys.iter()
+                .map(|&(time, value)| Keyframe {
+                    time,
+                    value,
+                    interpolation,
+                })
+                .collect(),
+        )
+    }
+
+    fn assert_samples(curve: &Curve<f32>, samples: &[(f32, f32)]) {
+        for &(time, expected) in samples {
+            let value = curve.sample(time).unwrap();
+            assert!(
+                (value - expected).abs() < 1e-5,
+                "{time}: {value} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn sample_interpolates_between_keys() {
+        let keys = [(0., 0.), (1., 4.), (2., 2.)];
+        assert_samples(
+            &curve(Interpolation::Step, &keys),
+            &[(0., 0.), (0.99, 0.), (1., 4.), (1.5, 4.)],
+        );
+        assert_samples(
+            &curve(Interpolation::Linear, &keys),
+            &[(0.25, 1.), (1., 4.), (1.5, 3.)],
+        );
+
+        // Evenly spaced keys on a line keep a Catmull-Rom spline on it.
+        let line = curve(
+            Interpolation::Smooth,
+            &[(0., 0.), (1., 1.), (2., 2.), (3., 3.)],
+        );
+        assert_samples(&line, &[(1., 1.), (1.5, 1.5), (2., 2.)]);
+        let smooth = curve(Interpolation::Smooth, &keys);
+        assert_samples(&smooth, &[(0., 0.), (1., 4.), (2., 2.)]);
+        assert!(smooth.sample(0.5).unwrap() > 2.);
+    }
+
+    #[test]
+    fn sample_holds_the_end_keys() {
+        let keys = curve(Interpolation::Linear, &[(0.5, 1.), (1., 3.)]);
+        assert_samples(&keys, &[(-1., 1.), (0.5, 1.), (1., 3.), (10., 3.)]);
+        assert_eq!(curve(Interpolation::Linear, &[]).sample(0.), None);
+    }
+
+    #[test]
+    fn sort_orders_keys_and_rejects_bad_times() {
+        let mut keys = curve(Interpolation::Linear, &[(1., 3.), (0., 1.)]);
+        assert_eq!(keys.sort(), Ok(()));
+        assert_samples(&keys, &[(0.5, 2.)]);
+
+        let mut duplicate = curve(Interpolation::Linear, &[(1., 0.), (0., 0.), (1., 1.)]);
+        assert_eq!(duplicate.sort(), Err(KeyframeError::Duplicate(1.)));
+
+        let mut nan = curve(Interpolation::Linear, &[(0., 0.), (f32::NAN, 1.)]);
+        assert!(matches!(nan.sort(), Err(KeyframeError::NonFinite(_))));
+    }
+}
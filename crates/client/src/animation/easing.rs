@@ -0,0 +1,125 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Maps linear progress in `0..=1` to eased progress, starting at 0 and ending at 1.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Reflect, Deserialize)]
+pub enum Ease {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    BackIn,
+    BackOut,
+    ElasticOut,
+    BounceOut,
+    /// Holds 0 until the end of the step, then jumps to 1.
+    Step,
+}
+
+impl Ease {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+
+        match self {
+            Self::Linear => t,
+            Self::QuadIn => t * t,
+            Self::QuadOut => 1. - (1. - t).powi(2),
+            Self::QuadInOut => {
+                if t < 0.5 {
+                    2. * t * t
+                } else {
+                    1. - (-2. * t + 2.).powi(2) / 2.
+                }
+            }
+            Self::CubicIn => t.powi(3),
+            Self::CubicOut => 1. - (1. - t).powi(3),
+            Self::CubicInOut => {
+                if t < 0.5 {
+                    4. * t.powi(3)
+                } else {
+                    1. - (-2. * t + 2.).powi(3) / 2.
+                }
+            }
+            Self::SineIn => 1. - (t * PI / 2.).cos(),
+            Self::SineOut => (t * PI / 2.).sin(),
+            Self::SineInOut => -((PI * t).cos() - 1.) / 2.,
+            Self::ExpoIn if t == 0. => 0.,
+            Self::ExpoIn => 2f32.powf(10. * t - 10.),
+            Self::ExpoOut if t == 1. => 1.,
+            Self::ExpoOut => 1. - 2f32.powf(-10. * t),
+            Self::BackIn => BACK_C3 * t.powi(3) - BACK_C1 * t * t,
+            Self::BackOut => 1. + BACK_C3 * (t - 1.).powi(3) + BACK_C1 * (t - 1.).powi(2),
+            Self::ElasticOut if t == 0. || t == 1. => t,
+            Self::ElasticOut => 2f32.powf(-10. * t) * ((t * 10. - 0.75) * TAU / 3.).sin() + 1.,
+            Self::BounceOut => bounce_out(t),
+            Self::Step if t < 1. => 0.,
+            Self::Step => 1.,
+        }
+    }
+}
+
+const BACK_C1: f32 = 1.70158;
+const BACK_C3: f32 = BACK_C1 + 1.;
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1. / D {
+        N * t * t
+    } else if t < 2. / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// One dimensional cubic bezier through the control values `points`.
+pub fn cubic_bezier(points: [f32; 4], t: f32) -> f32 {
+    let u = 1. - t;
+
+    u.powi(3) * points[0]
+        + 3. * u * u * t * points[1]
+        + 3. * u * t * t * points[2]
+        + t.powi(3) * points[3]
+}
+
+/// Decaying oscillation starting at 1 and settling at 0 when `t` reaches 1.
+/// `damping` controls how quickly the swings die down.
+pub fn spring(t: f32, oscillations: f32, damping: f32) -> f32 {
+    let t = t.clamp(0., 1.);
+    (1. - t) * (TAU * oscillations * t).cos() / (t + 1.).powf(damping)
+}
+
+/// Smooth value noise in `-1..=1`, different for every `seed`.
+pub fn noise(x: f32, seed: u32) -> f32 {
+    fn lattice(i: i32, seed: u32) -> f32 {
+        let mut h = (i as u32).wrapping_mul(0x27d4_eb2d) ^ seed.wrapping_mul(0x1656_67b1);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x85eb_ca6b);
+        h ^= h >> 13;
+        (h as f32 / u32::MAX as f32) * 2. - 1.
+    }
+
+    let i = x.floor();
+    let f = x - i;
+    let smooth = f * f * (3. - 2. * f);
+
+    lattice(i as i32, seed) * (1. - smooth) + lattice(i as i32 + 1, seed) * smooth
+}
@@ -0,0 +1,211 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{AnimationFreeze, AnimationOptions};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect, Deserialize)]
+pub enum PlaybackMode {
+    /// Stops on the last frame.
+    #[default]
+    Once,
+    Loop,
+    /// Plays forward then backward, without repeating the end frames.
+    PingPong,
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, Deserialize)]
+pub struct SpriteFrame {
+    /// Index into the entity's texture atlas.
+    pub index: usize,
+    /// Seconds the frame stays on screen.
+    pub duration: f32,
+    /// Sent as a [`SpriteFrameEvent`] whenever the frame is shown.
+    #[serde(default)]
+    pub event: Option<String>,
+}
+
+impl SpriteFrame {
+    pub fn new(index: usize, duration: f32) -> Self {
+        Self {
+            index,
+            duration,
+            event: None,
+        }
+    }
+
+    pub fn with_event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Reflect, Deserialize)]
+pub struct SpriteClip {
+    pub frames: Vec<SpriteFrame>,
+    #[serde(default)]
+    pub mode: PlaybackMode,
+}
+
+impl SpriteClip {
+    pub fn new(frames: impl IntoIterator<Item = SpriteFrame>, mode: PlaybackMode) -> Self {
+        Self {
+            frames: frames.into_iter().collect(),
+            mode,
+        }
+    }
+
+    /// Every index shown for the same `duration`.
+    pub fn uniform(
+        indices: impl IntoIterator<Item = usize>,
+        duration: f32,
+        mode: PlaybackMode,
+    ) -> Self {
+        Self::new(
+            indices
+                .into_iter()
+                .map(|index| SpriteFrame::new(index, duration)),
+            mode,
+        )
+    }
+}
+
+/// Cycles the [`TextureAtlasSprite`] index of its entity through a [`SpriteClip`].
+/// Runs independently of [`crate::Animator`], which can also pick the clip
+/// through [`crate::AnimatorStateMachine::sprite_clip`].
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
+pub struct SpriteAnimator {
+    pub clip: SpriteClip,
+    pub speed: f32,
+    frame: usize,
+    elapsed: f32,
+    reversing: bool,
+    finished: bool,
+    entered_frame: bool,
+}
+
+impl Default for SpriteAnimator {
+    fn default() -> Self {
+        Self::new(SpriteClip::default())
+    }
+}
+
+impl SpriteAnimator {
+    pub fn new(clip: SpriteClip) -> Self {
+        Self {
+            clip,
+            speed: 1.,
+            frame: 0,
+            elapsed: 0.,
+            reversing: false,
+            finished: false,
+            entered_frame: true,
+        }
+    }
+
+    /// Restarts playback from the first frame of `clip`.
+    pub fn play(&mut self, clip: SpriteClip) {
+        *self = Self {
+            speed: self.speed,
+            ..Self::new(clip)
+        };
+    }
+
+    pub fn frame(&self) -> Option<&SpriteFrame> {
+        self.clip.frames.get(self.frame)
+    }
+
+    /// Whether a [`PlaybackMode::Once`] clip has reached its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn advance(&mut self) {
+        let last = self.clip.frames.len().saturating_sub(1);
+        self.entered_frame = true;
+
+        match self.clip.mode {
+            PlaybackMode::Once if self.frame >= last => {
+                self.finished = true;
+                self.entered_frame = false;
+            }
+            PlaybackMode::Once => self.frame += 1,
+            PlaybackMode::Loop => {
+                self.frame = if self.frame >= last {
+                    0
+                } else {
+                    self.frame + 1
+                }
+            }
+            PlaybackMode::PingPong if last == 0 => self.entered_frame = false,
+            PlaybackMode::PingPong => {
+                if self.frame == last {
+                    self.reversing = true;
+                } else if self.frame == 0 {
+                    self.reversing = false;
+                }
+
+                self.frame = if self.reversing {
+                    self.frame - 1
+                } else {
+                    self.frame + 1
+                };
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Event)]
+pub struct SpriteFrameEvent {
+    pub entity: Entity,
+    pub event: String,
+}
+
+pub fn sprite_animator_system(
+    mut animators: Query<(
+        Entity,
+        &mut SpriteAnimator,
+        &mut TextureAtlasSprite,
+        Option<&AnimationFreeze>,
+    )>,
+    mut events: EventWriter<SpriteFrameEvent>,
+    time: Res<Time>,
+    options: Res<AnimationOptions>,
+) {
+    for (entity, mut animator, mut sprite, frozen) in animators.iter_mut() {
+        if animator.clip.frames.is_empty() {
+            continue;
+        }
+
+        if !animator.finished && frozen.is_none() {
+            animator.elapsed += time.delta_seconds() * animator.speed * options.global_speed;
+        }
+
+        // Several frames may pass in a single update when frames are short.
+        loop {
+            if animator.entered_frame {
+                animator.entered_frame = false;
+                if let Some(event) = animator.frame().and_then(|frame| frame.event.clone()) {
+                    events.send(SpriteFrameEvent { entity, event });
+                }
+            }
+
+            let duration = animator
+                .frame()
+                .map(|frame| frame.duration)
+                .unwrap_or_default();
+            if animator.finished || duration <= 0. || animator.elapsed < duration {
+                break;
+            }
+
+            animator.elapsed -= duration;
+            animator.advance();
+        }
+
+        if let Some(index) = animator.frame().map(|frame| frame.index) {
+            if sprite.index != index {
+                sprite.index = index;
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum LayerBlend {
+    /// Blends from the layers below towards this layer's pose by `weight`.
+    #[default]
+    Override,
+    /// Applies this layer's pose, scaled by `weight`, on top of the layers below.
+    Additive,
+}
+
+/// Where an [`crate::Animator`] sits in its entity's [`AnimationLayers`].
+/// Layers are applied in ascending `order`.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct AnimationLayer {
+    pub order: i32,
+    pub blend: LayerBlend,
+    pub weight: f32,
+}
+
+impl Default for AnimationLayer {
+    fn default() -> Self {
+        Self::new(0, LayerBlend::Override, 1.)
+    }
+}
+
+impl AnimationLayer {
+    pub fn new(order: i32, blend: LayerBlend, weight: f32) -> Self {
+        Self {
+            order,
+            blend,
+            weight,
+        }
+    }
+
+    pub fn additive(order: i32, weight: f32) -> Self {
+        Self::new(order, LayerBlend::Additive, weight)
+    }
+}
+
+/// Interpolates every part of a transform, `weight` 0 being `from` and 1 `to`.
+pub fn blend_transforms(from: &Transform, to: &Transform, weight: f32) -> Transform {
+    Transform {
+        translation: from.translation.lerp(to.translation, weight),
+        rotation: from.rotation.slerp(to.rotation, weight),
+        scale: from.scale.lerp(to.scale, weight),
+    }
+}
+
+/// Collects the poses of every animator on the entity during a frame and
+/// composes them into its [`Transform`]. Without this component an animator
+/// writes its pose to the transform directly.
+#[derive(Debug, Default, Clone, Component, Reflect)]
+#[reflect(Component)]
+pub struct AnimationLayers {
+    /// Pose the layers are applied to.
+    pub rest: Transform,
+    #[reflect(ignore)]
+    poses: Vec<(AnimationLayer, Transform)>,
+}
+
+impl AnimationLayers {
+    pub fn new(rest: Transform) -> Self {
+        Self {
+            rest,
+            poses: vec![],
+        }
+    }
+
+    pub fn push(&mut self, layer: AnimationLayer, pose: Transform) {
+        self.poses.push((layer, pose));
+    }
+
+    fn compose(&mut self) -> Option<Transform> {
+        if self.poses.is_empty() {
+            return None;
+        }
+
+        self.poses.sort_by_key(|(layer, _)| layer.order);
+
+        let composed = self
+            .poses
+            .drain(..)
+            .fold(self.rest, |below, (layer, pose)| match layer.blend {
+                LayerBlend::Override => blend_transforms(&below, &pose, layer.weight),
+                LayerBlend::Additive => {
+                    below * blend_transforms(&Transform::IDENTITY, &pose, layer.weight)
+                }
+            });
+
+        Some(composed)
+    }
+}
+
+pub fn compose_animation_layers(mut layered: Query<(&mut AnimationLayers, &mut Transform)>) {
+    for (mut layers, mut transform) in layered.iter_mut() {
+        if let Some(composed) = layers.compose() {
+            *transform = composed;
+        }
+    }
+}
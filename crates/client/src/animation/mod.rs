@@ -0,0 +1,473 @@
+use std::{borrow::Cow, marker::PhantomData, time::Duration};
+
+use bevy::{
+    prelude::*,
+    reflect::{GetTypeRegistration, TypePath},
+    transform::TransformSystem,
+};
+use bevy_inspector_egui::{prelude::ReflectInspectorOptions, InspectorOptions};
+
+use crate::SpriteColor;
+
+mod clip;
+mod easing;
+mod frames;
+mod layers;
+mod tween;
+
+pub use clip::*;
+pub use easing::*;
+pub use frames::*;
+pub use layers::*;
+pub use tween::*;
+
+/// Animates every [`Animator<T>`] and registers its events and reflection.
+pub struct AnimatorPlugin<T: AnimatorStateMachine>(PhantomData<T>);
+
+impl<T: AnimatorStateMachine> Default for AnimatorPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: AnimatorStateMachine> Plugin for AnimatorPlugin<T> {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<AnimatorCorePlugin>() {
+            app.add_plugins(AnimatorCorePlugin);
+        }
+
+        app.register_type::<Animator<T>>()
+            .add_event::<AnimationEvent<T>>()
+            .add_event::<AnimationFinished<T>>()
+            .add_systems(
+                PostUpdate,
+                animator_system::<T>.in_set(AnimationSet::Animate),
+            );
+    }
+}
+
+/// Systems shared by all animators. Animations run after gameplay has
+/// updated their states and before transforms are propagated, so the
+/// composed poses show up in the same frame.
+pub struct AnimatorCorePlugin;
+
+impl Plugin for AnimatorCorePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AnimationOptions>()
+            .init_resource::<AnimationOptions>()
+            .add_asset::<KeyframeClip>()
+            .init_asset_loader::<KeyframeClipLoader>()
+            .register_type::<SpriteAnimator>()
+            .register_type::<AnimationLayers>()
+            .register_type::<AnimationFreeze>()
+            .add_event::<SpriteFrameEvent>()
+            .configure_sets(
+                PostUpdate,
+                (AnimationSet::Animate, AnimationSet::Compose)
+                    .chain()
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    tick_animation_freezes.before(AnimationSet::Animate),
+                    (sprite_animator_system, tweener_system).in_set(AnimationSet::Animate),
+                    compose_animation_layers.in_set(AnimationSet::Compose),
+                ),
+            );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub enum AnimationSet {
+    Animate,
+    Compose,
+}
+
+#[derive(Resource, Reflect, InspectorOptions)]
+#[reflect(Resource, InspectorOptions)]
+pub struct AnimationOptions {
+    /// Multiplies the playback speed of every animator.
+    pub global_speed: f32,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self { global_speed: 1. }
+    }
+}
+
+/// Holds every animation on the entity in place, e.g. during hitstop.
+/// Counts down in real time, so it also runs out while game time is stopped.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct AnimationFreeze {
+    pub remaining: f32,
+}
+
+pub fn tick_animation_freezes(
+    mut commands: Commands,
+    mut freezes: Query<(Entity, &mut AnimationFreeze)>,
+    time: Res<Time>,
+) {
+    for (entity, mut freeze) in freezes.iter_mut() {
+        freeze.remaining -= time.raw_delta_seconds();
+        if freeze.remaining <= 0. {
+            commands.entity(entity).remove::<AnimationFreeze>();
+        }
+    }
+}
+
+/// Named point of a state's animation, `at` being the normalized time in `0..=1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationMarker {
+    pub at: f32,
+    pub name: &'static str,
+}
+
+impl AnimationMarker {
+    pub const fn new(at: f32, name: &'static str) -> Self {
+        Self { at, name }
+    }
+}
+
+pub trait AnimatorStateMachine
+where
+    Self: Default
+        + Sized
+        + Send
+        + Sync
+        + Clone
+        + Reflect
+        + FromReflect
+        + TypePath
+        + GetTypeRegistration
+        + 'static,
+{
+    fn calculate_transform(&self, t: f32) -> Transform;
+
+    fn duration(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    fn next(&self) -> Option<Self> {
+        Some(Self::default())
+    }
+
+    /// Markers sent as [`AnimationEvent`]s once the animation passes them.
+    fn markers(&self) -> &'static [AnimationMarker] {
+        &[]
+    }
+
+    /// Time spent cross-fading from the previous state when entering this one.
+    fn fade_in(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// Frame clip played by the entity's [`SpriteAnimator`] while in this state.
+    /// `None` leaves whatever clip is currently playing untouched.
+    fn sprite_clip(&self) -> Option<SpriteClip> {
+        None
+    }
+
+    /// Keyframed clip played while in this state. Once loaded, the clip's
+    /// duration replaces [`Self::duration`] and its events are sent like markers.
+    fn clip(&self, _asset_server: &AssetServer) -> Option<Handle<KeyframeClip>> {
+        None
+    }
+
+    /// Combines the state's own transform with the sampled clip.
+    fn apply_clip(&self, transform: Transform, pose: &ClipPose) -> Transform {
+        transform * pose.transform()
+    }
+}
+
+/// Pose of `state` at the timer's progress, combined with its clip when loaded.
+fn state_pose<T: AnimatorStateMachine>(
+    state: &T,
+    timer: &Timer,
+    clip: Option<&KeyframeClip>,
+) -> (Transform, Option<ClipPose>) {
+    let transform = state.calculate_transform(timer.percent());
+
+    match clip {
+        Some(clip) => {
+            let pose = clip.sample(timer.elapsed_secs());
+            (state.apply_clip(transform, &pose), Some(pose))
+        }
+        None => (transform, None),
+    }
+}
+
+#[derive(Clone)]
+struct Fade<T> {
+    from: T,
+    from_timer: Timer,
+    from_clip: Option<Handle<KeyframeClip>>,
+    timer: Timer,
+}
+
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Animator<T: AnimatorStateMachine> {
+    pub layer: AnimationLayer,
+    /// Playback speed of this animator, on top of [`AnimationOptions::global_speed`].
+    pub speed: f32,
+    pub paused: bool,
+    state: T,
+    timer: Timer,
+    #[reflect(ignore)]
+    clip: Option<Handle<KeyframeClip>>,
+    #[reflect(ignore)]
+    fade: Option<Fade<T>>,
+    entered_state: bool,
+}
+
+impl<T: AnimatorStateMachine> Default for Animator<T> {
+    fn default() -> Self {
+        T::default().into()
+    }
+}
+
+impl<T: AnimatorStateMachine> Animator<T> {
+    pub fn with_layer(mut self, layer: AnimationLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn state(&self) -> &T {
+        &self.state
+    }
+
+    pub fn transition_into(&mut self, state: T) -> T {
+        use std::mem::*;
+        let fade_in = state.fade_in();
+        let timer = replace(
+            &mut self.timer,
+            Timer::new(state.duration(), TimerMode::Once),
+        );
+        let clip = self.clip.take();
+        self.entered_state = true;
+        let old_state = replace(&mut self.state, state);
+
+        self.fade = (fade_in != Duration::ZERO).then(|| Fade {
+            from: old_state.clone(),
+            from_timer: timer,
+            from_clip: clip,
+            timer: Timer::new(fade_in, TimerMode::Once),
+        });
+
+        old_state
+    }
+
+    pub fn mutate_state(&mut self, mut mutator: impl FnMut(&mut T)) {
+        mutator(&mut self.state)
+    }
+
+    pub fn clip(&self) -> Option<&Handle<KeyframeClip>> {
+        self.clip.as_ref()
+    }
+
+    /// Current pose, blended with the previous state while cross-fading.
+    pub fn pose(&self, clips: &Assets<KeyframeClip>) -> (Transform, Option<ClipPose>) {
+        let clip = self.clip.as_ref().and_then(|clip| clips.get(clip));
+        let (transform, clip_pose) = state_pose(&self.state, &self.timer, clip);
+
+        let transform = match &self.fade {
+            Some(fade) => {
+                let from_clip = fade.from_clip.as_ref().and_then(|clip| clips.get(clip));
+                let (from, _) = state_pose(&fade.from, &fade.from_timer, from_clip);
+                blend_transforms(&from, &transform, fade.timer.percent())
+            }
+            None => transform,
+        };
+
+        (transform, clip_pose)
+    }
+
+    /// Resolves the clip and sprite clip of a freshly entered state.
+    fn enter_state(
+        &mut self,
+        asset_server: &AssetServer,
+        sprite_animator: Option<&mut SpriteAnimator>,
+    ) {
+        if !self.entered_state {
+            return;
+        }
+        self.entered_state = false;
+        self.clip = self.state.clip(asset_server);
+
+        if let (Some(sprite_animator), Some(clip)) = (sprite_animator, self.state.sprite_clip()) {
+            sprite_animator.play(clip);
+        }
+    }
+}
+
+impl<T: AnimatorStateMachine> From<T> for Animator<T> {
+    fn from(state: T) -> Self {
+        let duration = state.duration();
+
+        Self {
+            layer: AnimationLayer::default(),
+            speed: 1.,
+            paused: false,
+            state,
+            timer: Timer::new(duration, TimerMode::Once),
+            clip: None,
+            fade: None,
+            entered_state: true,
+        }
+    }
+}
+
+/// Sent when an animator passes one of its state's [`AnimationMarker`]s.
+#[derive(Debug, Clone, Event)]
+pub struct AnimationEvent<T: AnimatorStateMachine> {
+    pub entity: Entity,
+    pub marker: Cow<'static, str>,
+    pub state: T,
+}
+
+/// Sent when a timed state runs to completion, right before `next()` takes over.
+#[derive(Debug, Clone, Event)]
+pub struct AnimationFinished<T: AnimatorStateMachine> {
+    pub entity: Entity,
+    pub state: T,
+}
+
+type AnimatorQuery<'a, T> = (
+    Entity,
+    &'a mut Transform,
+    &'a mut Animator<T>,
+    Option<&'a mut AnimationLayers>,
+    Option<&'a mut SpriteAnimator>,
+    Option<&'a mut TextureAtlasSprite>,
+    Option<&'a mut SpriteColor>,
+    Option<&'a AnimationFreeze>,
+);
+
+pub fn animator_system<T: AnimatorStateMachine>(
+    mut animators: Query<AnimatorQuery<T>>,
+    mut marker_events: EventWriter<AnimationEvent<T>>,
+    mut finished_events: EventWriter<AnimationFinished<T>>,
+    time: Res<Time>,
+    options: Res<AnimationOptions>,
+    clips: Res<Assets<KeyframeClip>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (
+        entity,
+        mut transform,
+        mut animator,
+        layers,
+        mut sprite_animator,
+        sprite,
+        sprite_color,
+        frozen,
+    ) in animators.iter_mut()
+    {
+        animator.enter_state(&asset_server, sprite_animator.as_deref_mut());
+
+        let clip = animator.clip.as_ref().and_then(|clip| clips.get(clip));
+        let duration = clip
+            .map(|clip| Duration::from_secs_f32(clip.duration.max(0.)))
+            .unwrap_or_else(|| animator.state.duration());
+        if animator.timer.duration() != duration {
+            animator.timer.set_duration(duration);
+        }
+
+        let delta = if animator.paused || frozen.is_some() {
+            Duration::ZERO
+        } else {
+            time.delta()
+                .mul_f32((animator.speed * options.global_speed).max(0.))
+        };
+
+        if let Some(fade) = animator.fade.as_mut() {
+            fade.from_timer.tick(delta);
+            if fade.timer.tick(delta).finished() {
+                animator.fade = None;
+            }
+        }
+
+        let mut finished = false;
+        if duration != Duration::ZERO {
+            let from = animator.timer.percent();
+            let from_secs = animator.timer.elapsed_secs();
+            animator.timer.tick(delta);
+            let to = animator.timer.percent();
+            let to_secs = animator.timer.elapsed_secs();
+            finished = animator.timer.just_finished();
+
+            let passed = |at: f32, from: f32, to: f32| at >= from && (at < to || finished);
+            let markers = animator
+                .state
+                .markers()
+                .iter()
+                .filter(|marker| passed(marker.at, from, to))
+                .map(|marker| Cow::Borrowed(marker.name));
+            let clip_events = clip
+                .into_iter()
+                .flat_map(|clip| clip.events.iter())
+                .filter(|event| passed(event.time, from_secs, to_secs))
+                .map(|event| Cow::Owned(event.name.clone()));
+
+            for marker in markers.chain(clip_events) {
+                marker_events.send(AnimationEvent {
+                    entity,
+                    marker,
+                    state: animator.state.clone(),
+                });
+            }
+
+            if finished {
+                finished_events.send(AnimationFinished {
+                    entity,
+                    state: animator.state.clone(),
+                });
+            }
+        }
+
+        if finished {
+            if clip.is_some_and(|clip| clip.looping) {
+                animator.timer.reset();
+            } else {
+                let next_state = animator.state.next().unwrap_or_default();
+                animator.transition_into(next_state);
+                animator.enter_state(&asset_server, sprite_animator.as_deref_mut());
+            }
+        }
+
+        let (pose, clip_pose) = animator.pose(&clips);
+
+        match layers {
+            Some(mut layers) => layers.push(animator.layer, pose),
+            None => *transform = pose,
+        }
+
+        if let (Some(mut sprite), Some(index)) =
+            (sprite, clip_pose.and_then(|pose| pose.sprite_index))
+        {
+            sprite.index = index;
+        }
+
+        if let Some(mut sprite_color) = sprite_color {
+            let tint = clip_pose.and_then(|pose| pose.tint).unwrap_or(Color::WHITE);
+            if sprite_color.tint != tint {
+                sprite_color.tint = tint;
+            }
+        }
+    }
+}
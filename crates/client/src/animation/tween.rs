@@ -0,0 +1,438 @@
+use bevy::prelude::*;
+
+use super::{
+    noise, spring, AnimationFreeze, AnimationLayer, AnimationLayers, AnimationOptions, Ease,
+};
+use crate::SpriteColor;
+
+/// What a tween produces at a point in time. Translation offsets add up,
+/// rotations and scales multiply, and the last colour set wins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TweenPose {
+    pub transform: Transform,
+    pub color: Option<Color>,
+}
+
+impl Default for TweenPose {
+    fn default() -> Self {
+        Self {
+            transform: Transform::IDENTITY,
+            color: None,
+        }
+    }
+}
+
+impl TweenPose {
+    pub fn combine(self, other: Self) -> Self {
+        Self {
+            transform: Transform {
+                translation: self.transform.translation + other.transform.translation,
+                rotation: self.transform.rotation * other.transform.rotation,
+                scale: self.transform.scale * other.transform.scale,
+            },
+            color: other.color.or(self.color),
+        }
+    }
+}
+
+/// Blends two colours component-wise in their stored colour space.
+pub fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let from = Vec4::from_array(from.as_rgba_f32());
+    let [r, g, b, a] = from.lerp(Vec4::from_array(to.as_rgba_f32()), t).to_array();
+    Color::rgba(r, g, b, a)
+}
+
+/// A composable description of motion over time, sampled in seconds.
+/// Simple tweens describe one property, the others combine tweens: a
+/// [`Tween::Sequence`] only shows its active child, a [`Tween::Parallel`]
+/// combines all of its children.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tween {
+    Delay(f32),
+    Translate {
+        from: Vec3,
+        to: Vec3,
+        duration: f32,
+        ease: Ease,
+    },
+    /// Rotation around the z axis, in radians.
+    Rotate {
+        from: f32,
+        to: f32,
+        duration: f32,
+        ease: Ease,
+    },
+    Scale {
+        from: Vec3,
+        to: Vec3,
+        duration: f32,
+        ease: Ease,
+    },
+    Color {
+        from: Color,
+        to: Color,
+        duration: f32,
+        ease: Ease,
+    },
+    /// Swings around the rest pose with a decaying amplitude, see [`spring`].
+    Spring {
+        translation: Vec3,
+        rotation: f32,
+        oscillations: f32,
+        damping: f32,
+        duration: f32,
+    },
+    /// Noisy offset fading out over the duration.
+    Shake {
+        amplitude: Vec2,
+        frequency: f32,
+        duration: f32,
+        seed: u32,
+    },
+    Sequence(Vec<Tween>),
+    Parallel(Vec<Tween>),
+    /// Repeats forever when `times` is `None`.
+    Repeat {
+        tween: Box<Tween>,
+        times: Option<u32>,
+    },
+}
+
+impl Tween {
+    pub fn translate(from: Vec3, to: Vec3, duration: f32, ease: Ease) -> Self {
+        Self::Translate {
+            from,
+            to,
+            duration,
+            ease,
+        }
+    }
+
+    pub fn rotate(from: f32, to: f32, duration: f32, ease: Ease) -> Self {
+        Self::Rotate {
+            from,
+            to,
+            duration,
+            ease,
+        }
+    }
+
+    pub fn scale(from: Vec3, to: Vec3, duration: f32, ease: Ease) -> Self {
+        Self::Scale {
+            from,
+            to,
+            duration,
+            ease,
+        }
+    }
+
+    pub fn color(from: Color, to: Color, duration: f32, ease: Ease) -> Self {
+        Self::Color {
+            from,
+            to,
+            duration,
+            ease,
+        }
+    }
+
+    pub fn spring_translation(
+        translation: Vec3,
+        oscillations: f32,
+        damping: f32,
+        duration: f32,
+    ) -> Self {
+        Self::Spring {
+            translation,
+            rotation: 0.,
+            oscillations,
+            damping,
+            duration,
+        }
+    }
+
+    pub fn spring_rotation(rotation: f32, oscillations: f32, damping: f32, duration: f32) -> Self {
+        Self::Spring {
+            translation: Vec3::ZERO,
+            rotation,
+            oscillations,
+            damping,
+            duration,
+        }
+    }
+
+    pub fn shake(amplitude: Vec2, frequency: f32, duration: f32, seed: u32) -> Self {
+        Self::Shake {
+            amplitude,
+            frequency,
+            duration,
+            seed,
+        }
+    }
+
+    pub fn sequence(tweens: impl IntoIterator<Item = Tween>) -> Self {
+        Self::Sequence(tweens.into_iter().collect())
+    }
+
+    pub fn parallel(tweens: impl IntoIterator<Item = Tween>) -> Self {
+        Self::Parallel(tweens.into_iter().collect())
+    }
+
+    pub fn repeat(self, times: Option<u32>) -> Self {
+        Self::Repeat {
+            tween: Box::new(self),
+            times,
+        }
+    }
+
+    /// Total length in seconds, infinite for endless repeats.
+    pub fn duration(&self) -> f32 {
+        match self {
+            Self::Delay(duration)
+            | Self::Translate { duration, .. }
+            | Self::Rotate { duration, .. }
+            | Self::Scale { duration, .. }
+            | Self::Color { duration, .. }
+            | Self::Spring { duration, .. }
+            | Self::Shake { duration, .. } => *duration,
+            Self::Sequence(tweens) => tweens.iter().map(Tween::duration).sum(),
+            Self::Parallel(tweens) => tweens.iter().map(Tween::duration).fold(0., f32::max),
+            Self::Repeat { tween, times } => match times {
+                Some(times) => tween.duration() * *times as f32,
+                None => f32::INFINITY,
+            },
+        }
+    }
+
+    /// Samples the tween `time` seconds after it started. Times outside the
+    /// tween are clamped to its start or end.
+    pub fn sample(&self, time: f32) -> TweenPose {
+        let progress = |duration: f32| {
+            if duration > 0. {
+                (time / duration).clamp(0., 1.)
+            } else {
+                1.
+            }
+        };
+
+        match self {
+            Self::Delay(_) => TweenPose::default(),
+            Self::Translate {
+                from,
+                to,
+                duration,
+                ease,
+            } => TweenPose {
+                transform: Transform::from_translation(
+                    from.lerp(*to, ease.apply(progress(*duration))),
+                ),
+                ..default()
+            },
+            Self::Rotate {
+                from,
+                to,
+                duration,
+                ease,
+            } => {
+                let angle = *from + (*to - *from) * ease.apply(progress(*duration));
+                TweenPose {
+                    transform: Transform::from_rotation(Quat::from_rotation_z(angle)),
+                    ..default()
+                }
+            }
+            Self::Scale {
+                from,
+                to,
+                duration,
+                ease,
+            } => TweenPose {
+                transform: Transform::from_scale(from.lerp(*to, ease.apply(progress(*duration)))),
+                ..default()
+            },
+            Self::Color {
+                from,
+                to,
+                duration,
+                ease,
+            } => TweenPose {
+                color: Some(lerp_color(*from, *to, ease.apply(progress(*duration)))),
+                ..default()
+            },
+            Self::Spring {
+                translation,
+                rotation,
+                oscillations,
+                damping,
+                duration,
+            } => {
+                let swing = spring(progress(*duration), *oscillations, *damping);
+                TweenPose {
+                    transform: Transform {
+                        translation: *translation * swing,
+                        rotation: Quat::from_rotation_z(*rotation * swing),
+                        ..default()
+                    },
+                    ..default()
+                }
+            }
+            Self::Shake {
+                amplitude,
+                frequency,
+                duration,
+                seed,
+            } => {
+                let fade = 1. - progress(*duration);
+                let offset = Vec2::new(
+                    noise(time * frequency, *seed),
+                    noise(time * frequency, seed.wrapping_add(1)),
+                ) * *amplitude
+                    * fade;
+                TweenPose {
+                    transform: Transform::from_translation(offset.extend(0.)),
+                    ..default()
+                }
+            }
+            Self::Sequence(tweens) => {
+                let mut start = 0.;
+                for (i, tween) in tweens.iter().enumerate() {
+                    let duration = tween.duration();
+                    if time < start + duration || i == tweens.len() - 1 {
+                        return tween.sample(time - start);
+                    }
+                    start += duration;
+                }
+                TweenPose::default()
+            }
+            Self::Parallel(tweens) => tweens
+                .iter()
+                .map(|tween| tween.sample(time))
+                .fold(TweenPose::default(), TweenPose::combine),
+            Self::Repeat { tween, times } => {
+                let duration = tween.duration();
+                if duration <= 0. {
+                    return tween.sample(0.);
+                }
+
+                let finished = times.is_some_and(|times| time >= duration * times as f32);
+                if finished {
+                    tween.sample(duration)
+                } else {
+                    tween.sample(time.max(0.) % duration)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TweenFinish {
+    /// Leaves the tweener on the entity, holding the final pose.
+    #[default]
+    Keep,
+    Remove,
+    Despawn,
+}
+
+/// Plays a [`Tween`] on its entity's transform and sprite or text colour,
+/// for gameplay effects that do not warrant a whole state machine.
+#[derive(Debug, Clone, Component)]
+pub struct Tweener {
+    pub tween: Tween,
+    pub elapsed: f32,
+    pub speed: f32,
+    /// Pose the tween is applied to when the entity has no [`AnimationLayers`].
+    pub rest: Transform,
+    pub layer: AnimationLayer,
+    pub on_finish: TweenFinish,
+}
+
+impl Tweener {
+    pub fn new(tween: Tween) -> Self {
+        Self {
+            tween,
+            elapsed: 0.,
+            speed: 1.,
+            rest: Transform::IDENTITY,
+            layer: AnimationLayer::additive(0, 1.),
+            on_finish: TweenFinish::Keep,
+        }
+    }
+
+    pub fn with_rest(mut self, rest: Transform) -> Self {
+        self.rest = rest;
+        self
+    }
+
+    pub fn with_layer(mut self, layer: AnimationLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn on_finish(mut self, on_finish: TweenFinish) -> Self {
+        self.on_finish = on_finish;
+        self
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.tween.duration()
+    }
+}
+
+type TweenerQuery<'a> = (
+    Entity,
+    &'a mut Tweener,
+    &'a mut Transform,
+    Option<&'a mut AnimationLayers>,
+    Option<&'a mut TextureAtlasSprite>,
+    Option<&'a mut SpriteColor>,
+    Option<&'a mut Text>,
+    Option<&'a AnimationFreeze>,
+);
+
+pub fn tweener_system(
+    mut commands: Commands,
+    mut tweeners: Query<TweenerQuery>,
+    time: Res<Time>,
+    options: Res<AnimationOptions>,
+) {
+    for (entity, mut tweener, mut transform, layers, sprite, sprite_color, text, frozen) in
+        tweeners.iter_mut()
+    {
+        if frozen.is_none() {
+            tweener.elapsed += time.delta_seconds() * tweener.speed * options.global_speed;
+        }
+        let pose = tweener.tween.sample(tweener.elapsed);
+
+        match layers {
+            Some(mut layers) => layers.push(tweener.layer, pose.transform),
+            None => *transform = tweener.rest * pose.transform,
+        }
+
+        if let Some(color) = pose.color {
+            // Palette coloured sprites keep their palette colour underneath.
+            match (sprite_color, sprite) {
+                (Some(mut sprite_color), _) => {
+                    if sprite_color.tint != color {
+                        sprite_color.tint = color;
+                    }
+                }
+                (None, Some(mut sprite)) => sprite.color = color,
+                (None, None) => {}
+            }
+            if let Some(mut text) = text {
+                for section in text.sections.iter_mut() {
+                    section.style.color = color;
+                }
+            }
+        }
+
+        if tweener.is_finished() {
+            match tweener.on_finish {
+                TweenFinish::Keep => {}
+                TweenFinish::Remove => {
+                    commands.entity(entity).remove::<Tweener>();
+                }
+                TweenFinish::Despawn => commands.entity(entity).despawn_recursive(),
+            }
+        }
+    }
+}
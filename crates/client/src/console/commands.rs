@@ -0,0 +1,272 @@
+use bevy::{ecs::system::CommandQueue, prelude::*};
+use bevy_rapier2d::prelude::{DebugRenderContext, Velocity};
+
+use super::{AddConsoleCommand, CommandResult, Console, ConsoleCommand, ConsoleCommands};
+use crate::{
+    content::{spawn_dummy, ChestBundle, DummyCorpseBundle, DummySpawnerBundle, LevelExitBundle},
+    core::{DamageKind, DealDamageEvent, HealthChangedEvent, HealthPool, Invulnerable},
+    level::LevelEntity,
+    player::{CursorWorldPosition, PlayerMarker, PlayerMotor},
+    ActivePalette, PaletteLibrary, PaletteSet,
+};
+
+const ARCHETYPES: &[&str] = &["dummy", "corpse", "chest", "spawner", "exit"];
+const TARGETS: &[&str] = &["hero", "cursor"];
+
+pub(super) fn add_builtin_commands(app: &mut App) {
+    app.add_console_command(ConsoleCommand::new("help", "Lists all commands", help))
+        .add_console_command(ConsoleCommand::new("clear", "Clears the console", clear))
+        .add_console_command(
+            ConsoleCommand::new("spawn", "Spawns an entity at the cursor", spawn)
+                .with_usage("<archetype>")
+                .with_completions(ARCHETYPES),
+        )
+        .add_console_command(
+            ConsoleCommand::new("damage", "Deals damage to an entity", damage)
+                .with_usage("<amount> [hero|cursor|<entity>]")
+                .with_completions(TARGETS),
+        )
+        .add_console_command(
+            ConsoleCommand::new("heal", "Restores health of an entity", heal)
+                .with_usage("<amount> [hero|cursor|<entity>]")
+                .with_completions(TARGETS),
+        )
+        .add_console_command(ConsoleCommand::new(
+            "god",
+            "Toggles invulnerability of the hero",
+            god,
+        ))
+        .add_console_command(
+            ConsoleCommand::new(
+                "teleport",
+                "Moves the hero to a point or the cursor",
+                teleport,
+            )
+            .with_usage("[x y]"),
+        )
+        .add_console_command(
+            ConsoleCommand::new("timescale", "Sets how fast game time passes", timescale)
+                .with_usage("<scale>"),
+        )
+        .add_console_command(
+            ConsoleCommand::new(
+                "palette",
+                "Switches the active palette, or lists them",
+                palette,
+            )
+            .with_usage("[name]"),
+        )
+        .add_console_command(ConsoleCommand::new(
+            "physics_debug",
+            "Toggles the physics debug render",
+            physics_debug,
+        ));
+}
+
+fn cursor(world: &World) -> Result<Vec2, String> {
+    world
+        .resource::<CursorWorldPosition>()
+        .0
+        .ok_or_else(|| "the cursor is outside the window".to_string())
+}
+
+fn hero(world: &mut World) -> Result<Entity, String> {
+    world
+        .query_filtered::<Entity, With<PlayerMarker>>()
+        .iter(world)
+        .next()
+        .ok_or_else(|| "there is no hero".to_string())
+}
+
+/// Resolves `hero`, `cursor` (the entity with health closest to the
+/// cursor) or a raw entity index, defaulting to the hero.
+fn target(world: &mut World, arg: Option<&str>) -> Result<Entity, String> {
+    match arg.unwrap_or("hero") {
+        "hero" => hero(world),
+        "cursor" => {
+            let cursor = cursor(world)?;
+            world
+                .query_filtered::<(Entity, &GlobalTransform), With<HealthPool>>()
+                .iter(world)
+                .min_by(|(_, a), (_, b)| {
+                    let a = a.translation().truncate().distance_squared(cursor);
+                    let b = b.translation().truncate().distance_squared(cursor);
+                    a.total_cmp(&b)
+                })
+                .map(|(entity, _)| entity)
+                .ok_or_else(|| "nothing with health to target".to_string())
+        }
+        index => {
+            let index: u32 = index
+                .parse()
+                .map_err(|_| format!("`{index}` is not hero, cursor or an entity index"))?;
+            // Matched against the live entities, whatever generation they are in.
+            world
+                .query_filtered::<Entity, With<HealthPool>>()
+                .iter(world)
+                .find(|entity| entity.index() == index)
+                .ok_or_else(|| format!("entity {index} has no health"))
+        }
+    }
+}
+
+fn parse<T: std::str::FromStr>(arg: Option<&&str>, what: &str) -> Result<T, String> {
+    let arg = arg.ok_or_else(|| format!("missing {what}"))?;
+    arg.parse()
+        .map_err(|_| format!("`{arg}` is not a valid {what}"))
+}
+
+fn help(world: &mut World, _args: &[&str]) -> CommandResult {
+    Ok(world
+        .resource::<ConsoleCommands>()
+        .iter()
+        .map(|command| format!("{} {} - {}", command.name, command.usage, command.help))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn clear(world: &mut World, _args: &[&str]) -> CommandResult {
+    world.resource_mut::<Console>().lines.clear();
+    Ok(String::new())
+}
+
+fn spawn(world: &mut World, args: &[&str]) -> CommandResult {
+    let archetype = args.first().copied().unwrap_or_default();
+    let transform = Transform::from_translation(cursor(world)?.extend(0.));
+
+    let mut queue = CommandQueue::default();
+    let mut commands = Commands::new(&mut queue, world);
+    let entity = match archetype {
+        "dummy" => spawn_dummy(&mut commands, transform),
+        "corpse" => commands.spawn(DummyCorpseBundle::new(transform)).id(),
+        "chest" => commands.spawn(ChestBundle::new(transform)).id(),
+        "spawner" => commands.spawn(DummySpawnerBundle::new(transform)).id(),
+        "exit" => commands.spawn(LevelExitBundle::new(transform)).id(),
+        _ => {
+            return Err(format!(
+                "unknown archetype `{archetype}`, one of {}",
+                ARCHETYPES.join(", ")
+            ))
+        }
+    };
+    // Goes away with the level like everything else spawned into it.
+    commands.entity(entity).insert(LevelEntity);
+    queue.apply(world);
+
+    Ok(format!("spawned {archetype} {entity:?}"))
+}
+
+fn damage(world: &mut World, args: &[&str]) -> CommandResult {
+    let amount: u32 = parse(args.first(), "amount")?;
+    let target = target(world, args.get(1).copied())?;
+    let from_position = world
+        .get::<GlobalTransform>(target)
+        .map(|transform| transform.translation().truncate())
+        .unwrap_or_default();
+
+    world.send_event(DealDamageEvent {
+        from_position,
+        damage: amount,
+        kind: DamageKind::Physical,
+        target,
+        dealt_by: None,
+    });
+    Ok(format!("dealing {amount} damage to {target:?}"))
+}
+
+fn heal(world: &mut World, args: &[&str]) -> CommandResult {
+    let amount: u32 = parse(args.first(), "amount")?;
+    let target = target(world, args.get(1).copied())?;
+
+    let mut hp = world
+        .get_mut::<HealthPool>(target)
+        .ok_or_else(|| format!("{target:?} has no health"))?;
+    let previous_hp = hp.current_hp;
+    hp.current_hp = hp.current_hp.saturating_add(amount).min(hp.max_hp);
+    let event = HealthChangedEvent::new(target, previous_hp, &hp);
+
+    world.send_event(event);
+    Ok(format!(
+        "{target:?} now has {}/{} HP",
+        event.current_hp, event.max_hp
+    ))
+}
+
+fn god(world: &mut World, _args: &[&str]) -> CommandResult {
+    let hero = hero(world)?;
+    let mut hero = world.entity_mut(hero);
+
+    if hero.contains::<Invulnerable>() {
+        hero.remove::<Invulnerable>();
+        Ok("god mode off".into())
+    } else {
+        hero.insert(Invulnerable);
+        Ok("god mode on".into())
+    }
+}
+
+fn teleport(world: &mut World, args: &[&str]) -> CommandResult {
+    let destination = match args {
+        [] => cursor(world)?,
+        [x, y] => Vec2::new(parse(Some(x), "x")?, parse(Some(y), "y")?),
+        _ => return Err("expected no arguments or x and y".into()),
+    };
+    let hero = hero(world)?;
+
+    let mut hero = world.entity_mut(hero);
+    if let Some(mut transform) = hero.get_mut::<Transform>() {
+        transform.translation = destination.extend(transform.translation.z);
+    }
+    if let Some(mut velocity) = hero.get_mut::<Velocity>() {
+        velocity.linvel = Vec2::ZERO;
+    }
+    if let Some(mut motor) = hero.get_mut::<PlayerMotor>() {
+        motor.velocity = Vec2::ZERO;
+    }
+    Ok(format!("teleported to {destination}"))
+}
+
+fn timescale(world: &mut World, args: &[&str]) -> CommandResult {
+    let scale: f32 = parse(args.first(), "scale")?;
+    if !(0. ..=10.).contains(&scale) {
+        return Err("scale must be between 0 and 10".into());
+    }
+
+    world.resource_mut::<Time>().set_relative_speed(scale);
+    Ok(format!("time scale is {scale}"))
+}
+
+fn palette(world: &mut World, args: &[&str]) -> CommandResult {
+    let set = &world.resource::<PaletteLibrary>().set;
+    let mut names: Vec<String> = world
+        .resource::<Assets<PaletteSet>>()
+        .get(set)
+        .ok_or_else(|| "the palettes are still loading".to_string())?
+        .palettes
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+
+    let mut active = world.resource_mut::<ActivePalette>();
+    match args.first() {
+        None => Ok(format!("{} (active: {})", names.join(", "), active.name)),
+        Some(name) if names.iter().any(|known| known == name) => {
+            active.name = name.to_string();
+            Ok(format!("active palette is {name}"))
+        }
+        Some(name) => Err(format!(
+            "unknown palette `{name}`, one of {}",
+            names.join(", ")
+        )),
+    }
+}
+
+fn physics_debug(world: &mut World, _args: &[&str]) -> CommandResult {
+    let mut context = world.resource_mut::<DebugRenderContext>();
+    context.enabled = !context.enabled;
+    Ok(format!(
+        "physics debug render {}",
+        if context.enabled { "on" } else { "off" }
+    ))
+}
@@ -0,0 +1,300 @@
+mod commands;
+mod registry;
+
+use bevy::{input::InputSystem, prelude::*};
+use bevy_inspector_egui::{prelude::ReflectInspectorOptions, InspectorOptions};
+
+pub use registry::*;
+
+use commands::add_builtin_commands;
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ConsoleOptions>()
+            .init_resource::<ConsoleOptions>()
+            .init_resource::<Console>()
+            .init_resource::<ConsoleCommands>()
+            .add_systems(Startup, spawn_console)
+            .add_systems(PreUpdate, handle_console_input.after(InputSystem))
+            .add_systems(
+                Update,
+                (
+                    execute_console_commands,
+                    update_console_ui.after(execute_console_commands),
+                ),
+            );
+
+        add_builtin_commands(app);
+    }
+}
+
+#[derive(Resource, Reflect, InspectorOptions)]
+#[reflect(Resource, InspectorOptions)]
+pub struct ConsoleOptions {
+    pub toggle_key: KeyCode,
+    /// Lines of output kept and shown.
+    pub max_lines: usize,
+    pub max_history: usize,
+}
+
+impl Default for ConsoleOptions {
+    fn default() -> Self {
+        Self {
+            toggle_key: KeyCode::Grave,
+            max_lines: 16,
+            max_history: 64,
+        }
+    }
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct Console {
+    pub open: bool,
+    pub input: String,
+    pub lines: Vec<String>,
+    pub history: Vec<String>,
+    /// Position in `history` while browsing it with the arrow keys.
+    history_cursor: Option<usize>,
+    /// Submitted lines waiting to be run.
+    pending: Vec<String>,
+}
+
+impl Console {
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.lines.push(line.into());
+    }
+
+    /// Queues a command as if it was typed in.
+    pub fn submit(&mut self, line: impl Into<String>) {
+        self.pending.push(line.into());
+    }
+
+    fn browse_history(&mut self, older: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+
+        self.history_cursor = match (self.history_cursor, older) {
+            (None, true) => Some(self.history.len() - 1),
+            (Some(cursor), true) => Some(cursor.saturating_sub(1)),
+            (Some(cursor), false) if cursor + 1 < self.history.len() => Some(cursor + 1),
+            _ => None,
+        };
+        self.input = self
+            .history_cursor
+            .map(|cursor| self.history[cursor].clone())
+            .unwrap_or_default();
+    }
+
+    fn complete(&mut self, commands: &ConsoleCommands) {
+        let words: Vec<_> = self.input.split_whitespace().collect();
+        let completing_argument = words.len() > 1 || self.input.ends_with(' ');
+
+        let (prefix, candidates): (&str, Vec<&str>) = if completing_argument {
+            let Some(command) = words.first().and_then(|name| commands.get(name)) else {
+                return;
+            };
+            let partial = if self.input.ends_with(' ') {
+                ""
+            } else {
+                words.last().copied().unwrap_or_default()
+            };
+            (partial, command.completions.to_vec())
+        } else {
+            let partial = words.first().copied().unwrap_or_default();
+            (
+                partial,
+                commands.iter().map(|command| command.name).collect(),
+            )
+        };
+
+        let matches: Vec<_> = candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(prefix))
+            .collect();
+        let Some(first) = matches.first() else {
+            return;
+        };
+
+        let common = matches.iter().fold(first.to_string(), |common, candidate| {
+            common
+                .chars()
+                .zip(candidate.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect()
+        });
+
+        let kept = self.input.len() - prefix.len();
+        self.input.truncate(kept);
+        self.input.push_str(&common);
+        if matches.len() == 1 {
+            self.input.push(' ');
+        } else {
+            self.print(matches.join("  "));
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct ConsoleRoot;
+
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct ConsoleOutputText;
+
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct ConsoleInputText;
+
+pub fn spawn_console(mut commands: Commands) {
+    let style = TextStyle {
+        font_size: 16.,
+        color: Color::rgb_u8(0xC8, 0xAC, 0x93),
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.),
+                    width: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(8.)),
+                    row_gap: Val::Px(4.),
+                    ..default()
+                },
+                background_color: Color::rgba_u8(0x0A, 0x0D, 0x11, 0xE0).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(30),
+                ..default()
+            },
+            ConsoleRoot,
+            Name::new("Console"),
+        ))
+        .with_children(|console| {
+            console.spawn((
+                TextBundle::from_section("", style.clone()),
+                ConsoleOutputText,
+            ));
+            console.spawn((TextBundle::from_section("> ", style), ConsoleInputText));
+        });
+}
+
+/// Types into the console while it is open. Runs right after input is
+/// collected and swallows the keyboard, so gameplay and menus do not react
+/// to what is typed.
+pub fn handle_console_input(
+    mut console: ResMut<Console>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut characters: EventReader<ReceivedCharacter>,
+    commands: Res<ConsoleCommands>,
+    options: Res<ConsoleOptions>,
+) {
+    if keys.just_pressed(options.toggle_key) {
+        console.open = !console.open;
+        characters.clear();
+        keys.reset_all();
+        return;
+    }
+
+    if !console.open {
+        return;
+    }
+
+    for event in characters.iter() {
+        if !event.char.is_control() {
+            console.input.push(event.char);
+        }
+    }
+
+    if keys.just_pressed(KeyCode::Back) {
+        console.input.pop();
+    }
+    if keys.just_pressed(KeyCode::Tab) {
+        console.complete(&commands);
+    }
+    if keys.just_pressed(KeyCode::Up) {
+        console.browse_history(true);
+    }
+    if keys.just_pressed(KeyCode::Down) {
+        console.browse_history(false);
+    }
+    if keys.just_pressed(KeyCode::Escape) {
+        console.open = false;
+    }
+
+    if keys.just_pressed(KeyCode::Return) {
+        let line = std::mem::take(&mut console.input);
+        let line = line.trim();
+        console.history_cursor = None;
+
+        if !line.is_empty() {
+            if console.history.last().map(String::as_str) != Some(line) {
+                console.history.push(line.to_string());
+            }
+            let excess = console.history.len().saturating_sub(options.max_history);
+            console.history.drain(..excess);
+            console.submit(line);
+        }
+    }
+
+    keys.reset_all();
+}
+
+pub fn execute_console_commands(world: &mut World) {
+    let pending = std::mem::take(&mut world.resource_mut::<Console>().pending);
+
+    for line in pending {
+        let words: Vec<_> = line.split_whitespace().collect();
+        let Some((&name, args)) = words.split_first() else {
+            continue;
+        };
+
+        world.resource_mut::<Console>().print(format!("> {line}"));
+
+        let run = world.resource::<ConsoleCommands>().get(name).map(|c| c.run);
+        let result = match run {
+            Some(run) => run(world, args),
+            None => Err(format!("unknown command `{name}`, try `help`")),
+        };
+
+        let mut console = world.resource_mut::<Console>();
+        match result {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => console.print(output),
+            Err(err) => console.print(format!("error: {err}")),
+        }
+    }
+}
+
+pub fn update_console_ui(
+    mut console: ResMut<Console>,
+    mut roots: Query<&mut Visibility, With<ConsoleRoot>>,
+    mut outputs: Query<&mut Text, (With<ConsoleOutputText>, Without<ConsoleInputText>)>,
+    mut inputs: Query<&mut Text, With<ConsoleInputText>>,
+    options: Res<ConsoleOptions>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+
+    let excess = console.lines.len().saturating_sub(options.max_lines);
+    console.lines.drain(..excess);
+
+    for mut visibility in roots.iter_mut() {
+        *visibility = if console.open {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    for mut text in outputs.iter_mut() {
+        text.sections[0].value = console.lines.join("\n");
+    }
+    for mut text in inputs.iter_mut() {
+        text.sections[0].value = format!("> {}_", console.input);
+    }
+}
@@ -0,0 +1,76 @@
+use bevy::{prelude::*, utils::HashMap};
+
+/// Output of a command, printed to the console. Errors are highlighted.
+pub type CommandResult = Result<String, String>;
+
+/// Runs a command with its whitespace separated arguments.
+pub type CommandFn = fn(&mut World, &[&str]) -> CommandResult;
+
+#[derive(Clone)]
+pub struct ConsoleCommand {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub help: &'static str,
+    /// Values the first argument is autocompleted from.
+    pub completions: &'static [&'static str],
+    pub run: CommandFn,
+}
+
+impl ConsoleCommand {
+    pub fn new(name: &'static str, help: &'static str, run: CommandFn) -> Self {
+        Self {
+            name,
+            usage: "",
+            help,
+            completions: &[],
+            run,
+        }
+    }
+
+    pub fn with_usage(mut self, usage: &'static str) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    pub fn with_completions(mut self, completions: &'static [&'static str]) -> Self {
+        self.completions = completions;
+        self
+    }
+}
+
+/// Every command the console knows, registered by plugins through
+/// [`AddConsoleCommand::add_console_command`].
+#[derive(Default, Resource)]
+pub struct ConsoleCommands {
+    commands: HashMap<&'static str, ConsoleCommand>,
+}
+
+impl ConsoleCommands {
+    pub fn register(&mut self, command: ConsoleCommand) {
+        self.commands.insert(command.name, command);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ConsoleCommand> {
+        self.commands.get(name)
+    }
+
+    /// Commands sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = &ConsoleCommand> {
+        let mut commands: Vec<_> = self.commands.values().collect();
+        commands.sort_by_key(|command| command.name);
+        commands.into_iter()
+    }
+}
+
+pub trait AddConsoleCommand {
+    fn add_console_command(&mut self, command: ConsoleCommand) -> &mut Self;
+}
+
+impl AddConsoleCommand for App {
+    fn add_console_command(&mut self, command: ConsoleCommand) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(ConsoleCommands::default)
+            .register(command);
+        self
+    }
+}
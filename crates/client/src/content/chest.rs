@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::SpriteName;
+
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct Chest {
+    pub opened: bool,
+}
+
+#[derive(Bundle)]
+pub struct ChestBundle {
+    pub name: Name,
+    pub chest: Chest,
+    pub rb: RigidBody,
+    pub collider: Collider,
+    pub sprite_name: SpriteName,
+
+    #[bundle()]
+    pub spritesheet: SpriteSheetBundle,
+}
+
+impl ChestBundle {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            name: Name::new("Chest"),
+            chest: Chest::default(),
+            rb: RigidBody::Fixed,
+            collider: Collider::cuboid(3.5, 3.),
+            sprite_name: SpriteName::new("chest"),
+            spritesheet: SpriteSheetBundle {
+                transform,
+                ..default()
+            },
+        }
+    }
+}
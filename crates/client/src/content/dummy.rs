@@ -1,11 +1,14 @@
-use std::{f32::consts::PI, time::Duration};
+use std::time::Duration;
 
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::{
     core::{DamageTakenEvent, HealthPool},
-    Animator, AnimatorStateMachine, DUMMY, DUMMY_BROKEN,
+    fx::ImpactResponse,
+    level::LevelEntity,
+    progression::ExperienceReward,
+    Animator, AnimatorStateMachine, ClipPose, KeyframeClip, SpriteName,
 };
 
 #[derive(Component, Default)]
@@ -15,7 +18,9 @@ pub struct DummyBehaviour;
 pub struct DummyBodyBundle {
     pub behaviour: DummyBehaviour,
     pub hp: HealthPool,
+    pub reward: ExperienceReward,
     pub collider: Collider,
+    pub impact: ImpactResponse,
 
     #[bundle()]
     pub transform: TransformBundle,
@@ -27,15 +32,17 @@ impl Default for DummyBodyBundle {
     fn default() -> Self {
         Self {
             hp: HealthPool::new(10),
-            behaviour: DummyBehaviour::default(),
+            reward: ExperienceReward(3),
+            behaviour: DummyBehaviour,
             visibility: VisibilityBundle::default(),
             transform: TransformBundle::default(),
             collider: Collider::ball(4.),
+            impact: ImpactResponse::default(),
         }
     }
 }
 
-#[derive(Component, Default, Clone, Copy)]
+#[derive(Component, Default, Clone, Copy, Reflect)]
 pub enum DummyAnimationState {
     #[default]
     Idle,
@@ -45,46 +52,63 @@ pub enum DummyAnimationState {
 }
 
 impl AnimatorStateMachine for DummyAnimationState {
-    fn calculate_transform(&self, t: f32) -> Transform {
+    fn calculate_transform(&self, _t: f32) -> Transform {
+        Transform::IDENTITY
+    }
+
+    fn clip(&self, asset_server: &AssetServer) -> Option<Handle<KeyframeClip>> {
         match self {
-            DummyAnimationState::Idle => Transform::IDENTITY,
-            DummyAnimationState::Damaged {
-                relative_blow_direction,
-            } => {
-                let mut transform = Transform::IDENTITY;
-                transform.rotate_z(
-                    relative_blow_direction.x.signum() * 0.75 * (1. - t) * (2. * PI * t).cos()
-                        / (t + 1.).powi(2),
-                );
-                transform.translation -= (*relative_blow_direction * (1. - t).powi(2)).extend(0.);
-                transform
-            }
+            Self::Damaged { .. } => Some(asset_server.load("animations/dummy_hit.anim.ron")),
+            _ => None,
         }
     }
 
+    fn apply_clip(&self, transform: Transform, pose: &ClipPose) -> Transform {
+        let Self::Damaged {
+            relative_blow_direction,
+        } = self
+        else {
+            return transform;
+        };
+
+        let along_blow = pose.translation.unwrap_or_default().x;
+        let mut blow =
+            Transform::from_translation((*relative_blow_direction * along_blow).extend(0.));
+        blow.rotate_z(relative_blow_direction.x.signum() * pose.rotation.unwrap_or_default());
+
+        transform * blow
+    }
+
     fn duration(&self) -> Duration {
         match self {
             Self::Damaged { .. } => Duration::from_secs_f32(0.6),
             _ => Duration::ZERO,
         }
     }
+
+    fn fade_in(&self) -> Duration {
+        match self {
+            Self::Damaged { .. } => Duration::from_secs_f32(0.05),
+            _ => Duration::ZERO,
+        }
+    }
 }
 
 #[derive(Bundle)]
 pub struct DummySpriteBundle {
     pub animator: Animator<DummyAnimationState>,
+    pub sprite_name: SpriteName,
 
     #[bundle()]
     pub spritesheet: SpriteSheetBundle,
 }
 
-impl DummySpriteBundle {
-    pub fn new(texture_atlas: Handle<TextureAtlas>) -> Self {
+impl Default for DummySpriteBundle {
+    fn default() -> Self {
         Self {
             animator: Animator::default(),
+            sprite_name: SpriteName::new("dummy"),
             spritesheet: SpriteSheetBundle {
-                texture_atlas: texture_atlas.clone(),
-                sprite: DUMMY.clone(),
                 transform: Transform::IDENTITY,
                 ..default()
             },
@@ -92,14 +116,45 @@ impl DummySpriteBundle {
     }
 }
 
+pub fn spawn_dummy(commands: &mut Commands, transform: Transform) -> Entity {
+    commands
+        .spawn(DummyBodyBundle {
+            transform: TransformBundle::from_transform(transform),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(DummySpriteBundle::default());
+        })
+        .id()
+}
+
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct DummyCorpse;
+
 #[derive(Bundle)]
 pub struct DummyCorpseBundle {
+    pub corpse: DummyCorpse,
     pub collider: Collider,
+    pub sprite_name: SpriteName,
 
     #[bundle()]
     pub spritesheet: SpriteSheetBundle,
 }
 
+impl DummyCorpseBundle {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            corpse: DummyCorpse,
+            collider: Collider::ball(4.),
+            sprite_name: SpriteName::new("dummy_broken"),
+            spritesheet: SpriteSheetBundle {
+                transform,
+                ..Default::default()
+            },
+        }
+    }
+}
+
 pub fn dummy_damage_shake(
     mut dummy_animators: Query<(
         &Parent,
@@ -127,27 +182,13 @@ pub fn dummy_damage_shake(
 }
 
 pub fn tick_dummy_sprite(
-    mut dummies: Query<(Entity, &HealthPool, &Transform, &Children), With<DummyBehaviour>>,
-    dummy_sprites: Query<&Handle<TextureAtlas>>,
+    mut dummies: Query<(Entity, &HealthPool, &Transform), With<DummyBehaviour>>,
     mut commands: Commands,
 ) {
-    for (entt, hp, transform, children) in dummies.iter_mut() {
+    for (entt, hp, transform) in dummies.iter_mut() {
         if hp.just_died {
-            let atlas = dummy_sprites
-                .get(*children.get(0).unwrap())
-                .unwrap()
-                .clone();
-
             commands.entity(entt).despawn_recursive();
-            commands.spawn(DummyCorpseBundle {
-                spritesheet: SpriteSheetBundle {
-                    sprite: DUMMY_BROKEN.clone(),
-                    texture_atlas: atlas.clone(),
-                    transform: *transform,
-                    ..Default::default()
-                },
-                collider: Collider::ball(4.),
-            });
+            commands.spawn((DummyCorpseBundle::new(*transform), LevelEntity));
         }
     }
 }
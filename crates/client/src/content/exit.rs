@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+use crate::{player::CameraTarget, SpriteName};
+
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct LevelExit;
+
+#[derive(Bundle)]
+pub struct LevelExitBundle {
+    pub name: Name,
+    pub exit: LevelExit,
+    pub sprite_name: SpriteName,
+    pub camera_target: CameraTarget,
+
+    #[bundle()]
+    pub spritesheet: SpriteSheetBundle,
+}
+
+impl LevelExitBundle {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            name: Name::new("Exit"),
+            exit: LevelExit,
+            sprite_name: SpriteName::new("exit"),
+            // Pulled into view as the hero gets close.
+            camera_target: CameraTarget::new(0.5, 16.).with_range(96.),
+            spritesheet: SpriteSheetBundle {
+                transform,
+                ..default()
+            },
+        }
+    }
+}
@@ -1,3 +1,9 @@
+mod chest;
 mod dummy;
+mod exit;
+mod spawner;
 
+pub use chest::*;
 pub use dummy::*;
+pub use exit::*;
+pub use spawner::*;
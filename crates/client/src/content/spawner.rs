@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use super::{spawn_dummy, DummyBehaviour};
+use crate::level::LevelEntity;
+
+/// Keeps a single dummy alive at its position, replacing it after it dies
+/// until it runs out of dummies.
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct DummySpawner {
+    pub remaining: u32,
+    pub cooldown: Timer,
+    #[reflect(ignore)]
+    pub alive: Option<Entity>,
+}
+
+impl Default for DummySpawner {
+    fn default() -> Self {
+        Self {
+            remaining: 3,
+            cooldown: Timer::from_seconds(2., TimerMode::Once),
+            alive: None,
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct DummySpawnerBundle {
+    pub name: Name,
+    pub spawner: DummySpawner,
+
+    #[bundle()]
+    pub spatial: SpatialBundle,
+}
+
+impl DummySpawnerBundle {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            name: Name::new("Dummy Spawner"),
+            spawner: DummySpawner::default(),
+            spatial: SpatialBundle::from_transform(transform),
+        }
+    }
+}
+
+pub fn tick_dummy_spawners(
+    mut commands: Commands,
+    mut spawners: Query<(&Transform, &mut DummySpawner)>,
+    dummies: Query<(), With<DummyBehaviour>>,
+    time: Res<Time>,
+) {
+    for (transform, mut spawner) in spawners.iter_mut() {
+        if spawner.alive.is_some_and(|dummy| dummies.contains(dummy)) {
+            continue;
+        }
+
+        spawner.alive = None;
+        if spawner.remaining == 0 {
+            continue;
+        }
+
+        spawner.cooldown.tick(time.delta());
+        if !spawner.cooldown.finished() {
+            continue;
+        }
+
+        let dummy = spawn_dummy(&mut commands, *transform);
+        commands.entity(dummy).insert(LevelEntity);
+
+        spawner.alive = Some(dummy);
+        spawner.remaining -= 1;
+        spawner.cooldown.reset();
+    }
+}
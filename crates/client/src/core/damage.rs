@@ -1,26 +1,42 @@
 use bevy::prelude::*;
 
-use super::HealthPool;
+use super::{HealthChangedEvent, HealthPool, Invulnerable};
+
+/// What kind of harm a hit deals, used to tell hits apart in feedback.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum DamageKind {
+    #[default]
+    Physical,
+    Critical,
+    Fire,
+    Poison,
+}
 
 #[derive(Debug, Event, Clone, Copy)]
 pub struct DealDamageEvent {
     pub from_position: Vec2,
     pub damage: u32,
+    pub kind: DamageKind,
     pub target: Entity,
+    pub dealt_by: Option<Entity>,
 }
 
 #[derive(Debug, Event)]
 pub struct DamageTakenEvent {
     pub from_position: Vec2,
     pub damage: u32,
+    pub kind: DamageKind,
     pub taken_by: Entity,
+    pub dealt_by: Option<Entity>,
     pub killing_blow: bool,
 }
 
 pub fn damage_system(
     mut health_pools: Query<(Entity, &mut HealthPool)>,
+    invulnerable: Query<(), With<Invulnerable>>,
     mut damage_deal: EventReader<DealDamageEvent>,
     mut damage_taken: EventWriter<DamageTakenEvent>,
+    mut health_changed: EventWriter<HealthChangedEvent>,
 ) {
     for (_entity, mut pool) in health_pools.iter_mut() {
         pool.just_died = false;
@@ -29,14 +45,16 @@ pub fn damage_system(
     for DealDamageEvent {
         target,
         damage,
+        kind,
         from_position,
+        dealt_by,
     } in damage_deal.into_iter()
     {
         let Ok((entity, mut hp)) = health_pools.get_mut(*target) else {
             continue;
         };
 
-        if hp.current_hp == 0 {
+        if hp.current_hp == 0 || invulnerable.contains(entity) {
             continue;
         }
 
@@ -44,12 +62,16 @@ pub fn damage_system(
             hp.just_died = true;
         }
 
+        let previous_hp = hp.current_hp;
         hp.current_hp = hp.current_hp.saturating_sub(*damage);
+        health_changed.send(HealthChangedEvent::new(entity, previous_hp, &hp));
 
         damage_taken.send(DamageTakenEvent {
             damage: *damage,
+            kind: *kind,
             from_position: *from_position,
             taken_by: entity,
+            dealt_by: *dealt_by,
             killing_blow: hp.just_died,
         })
 
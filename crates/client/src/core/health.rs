@@ -16,4 +16,44 @@ impl HealthPool {
             just_died: false,
         }
     }
+
+    /// Current health as a fraction of the maximum, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        if self.max_hp == 0 {
+            return 0.;
+        }
+        (self.current_hp as f32 / self.max_hp as f32).clamp(0., 1.)
+    }
+}
+
+/// Ignores all incoming damage, e.g. for the console's god mode.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct Invulnerable;
+
+/// Sent whenever the current or maximum health of a [`HealthPool`] changes,
+/// so displays do not have to watch every pool.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct HealthChangedEvent {
+    pub entity: Entity,
+    pub previous_hp: u32,
+    pub current_hp: u32,
+    pub max_hp: u32,
+}
+
+impl HealthChangedEvent {
+    pub fn new(entity: Entity, previous_hp: u32, pool: &HealthPool) -> Self {
+        Self {
+            entity,
+            previous_hp,
+            current_hp: pool.current_hp,
+            max_hp: pool.max_hp,
+        }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.max_hp == 0 {
+            return 0.;
+        }
+        (self.current_hp as f32 / self.max_hp as f32).clamp(0., 1.)
+    }
 }
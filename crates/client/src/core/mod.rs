@@ -1,16 +1,23 @@
 mod damage;
 mod health;
 
-use bevy::prelude::Update;
+use bevy::prelude::{IntoSystemConfigs, SystemSet, Update};
 pub use damage::*;
 pub use health::*;
 
+/// Systems that simulate the world and should halt whenever the game is
+/// waiting on the player, e.g. while a level-up choice is on screen.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct GameplaySet;
+
 pub struct CorePlugin;
 
 impl bevy::prelude::Plugin for CorePlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_event::<DealDamageEvent>()
+        app.register_type::<Invulnerable>()
+            .add_event::<DealDamageEvent>()
             .add_event::<DamageTakenEvent>()
-            .add_systems(Update, damage_system);
+            .add_event::<HealthChangedEvent>()
+            .add_systems(Update, damage_system.in_set(GameplaySet));
     }
 }
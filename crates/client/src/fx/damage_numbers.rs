@@ -0,0 +1,280 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::{prelude::ReflectInspectorOptions, InspectorOptions};
+
+use crate::{
+    core::{DamageKind, DamageTakenEvent},
+    player::PlayerMarker,
+    Ease, Tween, Tweener,
+};
+
+/// Keeps numbers above sprites and tiles.
+const NUMBER_Z: f32 = 50.;
+/// Pixels above the target's origin a number appears at.
+const NUMBER_OFFSET: f32 = 6.;
+
+#[derive(Resource, Reflect, InspectorOptions)]
+#[reflect(Resource, InspectorOptions)]
+pub struct DamageNumberOptions {
+    pub physical_color: Color,
+    pub critical_color: Color,
+    pub fire_color: Color,
+    pub poison_color: Color,
+    /// Used for damage the hero takes, whatever its kind.
+    pub hero_color: Color,
+    pub font_size: f32,
+    /// Hits of the same kind on the same target within this many seconds
+    /// add up into one number.
+    pub merge_window: f32,
+    pub lifetime: f32,
+    /// Pixels a number rises over its lifetime.
+    pub drift: f32,
+    /// Random sideways offset, so numbers on the same spot do not overlap.
+    pub jitter: f32,
+    pub killing_blow_scale: f32,
+    /// Most numbers shown at once, the oldest is reused past this.
+    pub max_numbers: usize,
+}
+
+impl Default for DamageNumberOptions {
+    fn default() -> Self {
+        Self {
+            physical_color: Color::rgb_u8(0xF2, 0xEE, 0xE4),
+            critical_color: Color::rgb_u8(0xF5, 0xC2, 0x42),
+            fire_color: Color::rgb_u8(0xE8, 0x6A, 0x33),
+            poison_color: Color::rgb_u8(0x8C, 0xC8, 0x4B),
+            hero_color: Color::rgb_u8(0xD9, 0x3F, 0x4A),
+            font_size: 8.,
+            merge_window: 0.35,
+            lifetime: 0.8,
+            drift: 10.,
+            jitter: 3.,
+            killing_blow_scale: 1.6,
+            max_numbers: 32,
+        }
+    }
+}
+
+impl DamageNumberOptions {
+    pub fn color(&self, kind: DamageKind) -> Color {
+        match kind {
+            DamageKind::Physical => self.physical_color,
+            DamageKind::Critical => self.critical_color,
+            DamageKind::Fire => self.fire_color,
+            DamageKind::Poison => self.poison_color,
+        }
+    }
+}
+
+/// A pooled floating number. Inactive numbers are hidden and wait to be
+/// reused rather than despawned.
+#[derive(Debug, Clone, Component)]
+pub struct DamageNumber {
+    pub target: Entity,
+    pub kind: DamageKind,
+    pub amount: u32,
+    pub killing_blow: bool,
+    /// Elapsed seconds when the number last changed.
+    pub shown_at: f32,
+    pub active: bool,
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct DamageNumberPool {
+    pub spawned: usize,
+}
+
+/// Damage that should show up as one number.
+struct Hit {
+    target: Entity,
+    kind: DamageKind,
+    amount: u32,
+    killing_blow: bool,
+    color: Color,
+    position: Vec2,
+}
+
+fn number_tween(color: Color, options: &DamageNumberOptions) -> Tween {
+    let fade = options.lifetime * 0.5;
+
+    Tween::parallel([
+        Tween::translate(
+            Vec3::ZERO,
+            Vec3::Y * options.drift,
+            options.lifetime,
+            Ease::QuadOut,
+        ),
+        Tween::sequence([
+            Tween::scale(Vec3::splat(0.3), Vec3::splat(1.3), 0.1, Ease::BackOut),
+            Tween::scale(Vec3::splat(1.3), Vec3::ONE, 0.12, Ease::QuadOut),
+        ]),
+        Tween::sequence([
+            Tween::Delay(options.lifetime - fade),
+            Tween::color(color, color.with_a(0.), fade, Ease::QuadIn),
+        ]),
+    ])
+}
+
+fn number_text(hit: &Hit, options: &DamageNumberOptions) -> Text {
+    Text::from_section(
+        hit.amount.to_string(),
+        TextStyle {
+            font_size: options.font_size,
+            color: hit.color,
+            ..default()
+        },
+    )
+    .with_alignment(TextAlignment::Center)
+}
+
+fn number_rest(position: Vec2, killing_blow: bool, options: &DamageNumberOptions) -> Transform {
+    let scale = if killing_blow {
+        options.killing_blow_scale
+    } else {
+        1.
+    };
+
+    Transform::from_translation(position.extend(NUMBER_Z)).with_scale(Vec3::splat(scale))
+}
+
+pub fn spawn_damage_numbers(
+    mut commands: Commands,
+    mut events: EventReader<DamageTakenEvent>,
+    mut numbers: Query<(
+        Entity,
+        &mut DamageNumber,
+        &mut Text,
+        &mut Transform,
+        &mut Visibility,
+        &mut Tweener,
+    )>,
+    targets: Query<(&GlobalTransform, Option<&PlayerMarker>)>,
+    mut pool: ResMut<DamageNumberPool>,
+    time: Res<Time>,
+    options: Res<DamageNumberOptions>,
+) {
+    let now = time.elapsed_seconds();
+
+    // Hits landing on the same frame always merge.
+    let mut hits: Vec<Hit> = vec![];
+    for event in events.iter() {
+        let Ok((target, hero)) = targets.get(event.taken_by) else {
+            continue;
+        };
+
+        match hits
+            .iter_mut()
+            .find(|hit| hit.target == event.taken_by && hit.kind == event.kind)
+        {
+            Some(hit) => {
+                hit.amount += event.damage;
+                hit.killing_blow |= event.killing_blow;
+            }
+            None => hits.push(Hit {
+                target: event.taken_by,
+                kind: event.kind,
+                amount: event.damage,
+                killing_blow: event.killing_blow,
+                color: if hero.is_some() {
+                    options.hero_color
+                } else {
+                    options.color(event.kind)
+                },
+                position: target.translation().truncate()
+                    + Vec2::new((fastrand::f32() * 2. - 1.) * options.jitter, NUMBER_OFFSET),
+            }),
+        }
+    }
+
+    for mut hit in hits {
+        let merge_with = numbers
+            .iter()
+            .find(|(_, number, ..)| {
+                number.active
+                    && number.target == hit.target
+                    && number.kind == hit.kind
+                    && now - number.shown_at <= options.merge_window
+            })
+            .map(|(entity, ..)| entity);
+
+        let reuse = merge_with
+            .or_else(|| {
+                numbers
+                    .iter()
+                    .find(|(_, number, ..)| !number.active)
+                    .map(|(entity, ..)| entity)
+            })
+            .or_else(|| {
+                // Out of numbers, take over the oldest one.
+                numbers
+                    .iter()
+                    .filter(|_| pool.spawned >= options.max_numbers)
+                    .min_by(|(_, a, ..), (_, b, ..)| a.shown_at.total_cmp(&b.shown_at))
+                    .map(|(entity, ..)| entity)
+            });
+
+        let Some(entity) = reuse else {
+            if pool.spawned >= options.max_numbers {
+                continue;
+            }
+
+            pool.spawned += 1;
+            commands.spawn((
+                Name::new("Damage Number"),
+                Text2dBundle {
+                    text: number_text(&hit, &options),
+                    transform: number_rest(hit.position, hit.killing_blow, &options),
+                    ..default()
+                },
+                DamageNumber {
+                    target: hit.target,
+                    kind: hit.kind,
+                    amount: hit.amount,
+                    killing_blow: hit.killing_blow,
+                    shown_at: now,
+                    active: true,
+                },
+                Tweener::new(number_tween(hit.color, &options)).with_rest(number_rest(
+                    hit.position,
+                    hit.killing_blow,
+                    &options,
+                )),
+            ));
+            continue;
+        };
+
+        let Ok((_, mut number, mut text, mut transform, mut visibility, mut tweener)) =
+            numbers.get_mut(entity)
+        else {
+            continue;
+        };
+
+        if merge_with.is_some() {
+            hit.amount += number.amount;
+            hit.killing_blow |= number.killing_blow;
+            hit.position = tweener.rest.translation.truncate();
+        }
+
+        *number = DamageNumber {
+            target: hit.target,
+            kind: hit.kind,
+            amount: hit.amount,
+            killing_blow: hit.killing_blow,
+            shown_at: now,
+            active: true,
+        };
+        *text = number_text(&hit, &options);
+        *transform = number_rest(hit.position, hit.killing_blow, &options);
+        *visibility = Visibility::Inherited;
+        *tweener = Tweener::new(number_tween(hit.color, &options)).with_rest(*transform);
+    }
+}
+
+/// Hides numbers that finished animating so they can be reused.
+pub fn recycle_damage_numbers(mut numbers: Query<(&mut DamageNumber, &Tweener, &mut Visibility)>) {
+    for (mut number, tweener, mut visibility) in numbers.iter_mut() {
+        if number.active && tweener.is_finished() {
+            number.active = false;
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    level::{TileKind, TileMap},
+    particles::{spawn_particle_effect, ParticleEmitter},
+    player::PlayerMotor,
+    TILE_SIZE,
+};
+
+/// Pixels per second a hero has to move at to kick up dust or grass.
+const MIN_STIR_SPEED: f32 = 20.;
+
+/// Marks the emitter under a hero's feet, running while the hero moves.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct FootstepDust;
+
+pub fn footstep_dust(
+    motors: Query<&PlayerMotor>,
+    mut emitters: Query<(&Parent, &mut ParticleEmitter), With<FootstepDust>>,
+) {
+    for (parent, mut emitter) in emitters.iter_mut() {
+        let Ok(motor) = motors.get(parent.get()) else {
+            continue;
+        };
+
+        let moving = motor.velocity.length() > MIN_STIR_SPEED;
+        if emitter.active != moving {
+            emitter.active = moving;
+        }
+        if moving {
+            emitter.direction = -motor.velocity.normalize();
+        }
+    }
+}
+
+/// Rustles the grass whenever a hero steps onto a new grass tile.
+pub fn grass_rustle(
+    mut commands: Commands,
+    heroes: Query<(Entity, &GlobalTransform, &PlayerMotor)>,
+    maps: Query<&TileMap>,
+    mut last_cells: Local<HashMap<Entity, IVec2>>,
+) {
+    let Ok(map) = maps.get_single() else {
+        return;
+    };
+
+    for (hero, transform, motor) in heroes.iter() {
+        let position = transform.translation().truncate();
+        let cell = ((position - map.origin()) / TILE_SIZE).floor().as_ivec2();
+        if last_cells.insert(hero, cell) == Some(cell) {
+            continue;
+        }
+
+        if map.get(cell) == Some(TileKind::Grass) && motor.velocity.length() > MIN_STIR_SPEED {
+            spawn_particle_effect(&mut commands, "grass_rustle", position, motor.velocity);
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_inspector_egui::{prelude::ReflectInspectorOptions, InspectorOptions};
+
+use crate::{
+    core::DamageTakenEvent, particles::spawn_particle_effect, AnimationFreeze, SpriteColor,
+};
+
+/// Pixels from the target's center towards the attacker where hits land.
+const CONTACT_OFFSET: f32 = 3.;
+
+#[derive(Resource, Reflect, InspectorOptions)]
+#[reflect(Resource, InspectorOptions)]
+pub struct ImpactOptions {
+    pub hit_flash: bool,
+    pub hitstop: bool,
+    pub particles: bool,
+    /// Longest game time may stand still, whatever the weapon asks for.
+    pub max_time_freeze: f32,
+}
+
+impl Default for ImpactOptions {
+    fn default() -> Self {
+        Self {
+            hit_flash: true,
+            hitstop: true,
+            particles: true,
+            max_time_freeze: 0.1,
+        }
+    }
+}
+
+/// How the hits of a weapon feel, on the entity dealing the damage.
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct WeaponImpact {
+    /// Seconds the attacker's and target's animations hold on hit.
+    pub hitstop: f32,
+    pub killing_blow_hitstop: f32,
+    /// Also stops game time for the hitstop, for especially heavy blows.
+    pub freeze_time: bool,
+    /// Particle effect flying off the contact point.
+    pub sparks: Option<String>,
+}
+
+impl Default for WeaponImpact {
+    fn default() -> Self {
+        Self {
+            hitstop: 0.05,
+            killing_blow_hitstop: 0.12,
+            freeze_time: false,
+            sparks: Some("sparks".into()),
+        }
+    }
+}
+
+/// How an entity reacts to being hit, on the entity taking the damage.
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct ImpactResponse {
+    pub flash_color: Color,
+    pub flash_duration: f32,
+    /// Scales the attacker's hitstop, e.g. lower for swarms of small enemies.
+    pub hitstop_scale: f32,
+    /// Particle effect knocked off the target on every hit.
+    pub debris: Option<String>,
+    /// Particle effect played where the target dies.
+    pub death: Option<String>,
+}
+
+impl Default for ImpactResponse {
+    fn default() -> Self {
+        Self {
+            flash_color: Color::WHITE,
+            flash_duration: 0.1,
+            hitstop_scale: 1.,
+            debris: Some("debris".into()),
+            death: Some("death_puff".into()),
+        }
+    }
+}
+
+/// Tints a sprite towards `color`, fading back over the timer.
+#[derive(Debug, Clone, Component)]
+pub struct HitFlash {
+    pub color: Color,
+    pub timer: Timer,
+}
+
+impl HitFlash {
+    pub fn new(color: Color, duration: f32) -> Self {
+        Self {
+            color,
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+        }
+    }
+}
+
+/// Stops game time for a moment. Counts down in real time.
+#[derive(Debug, Default, Resource)]
+pub struct TimeFreeze {
+    pub remaining: f32,
+    resume_speed: Option<f32>,
+}
+
+/// What a hit looks up about the attacker and the target.
+#[derive(SystemParam)]
+pub struct ImpactSources<'w, 's> {
+    weapons: Query<'w, 's, &'static WeaponImpact>,
+    responses: Query<'w, 's, &'static ImpactResponse>,
+    transforms: Query<'w, 's, &'static GlobalTransform>,
+    children: Query<'w, 's, &'static Children>,
+    sprites: Query<'w, 's, (), With<TextureAtlasSprite>>,
+}
+
+#[derive(SystemParam)]
+pub struct TimeFreezer<'w> {
+    freeze: ResMut<'w, TimeFreeze>,
+    time: ResMut<'w, Time>,
+}
+
+impl<'w> TimeFreezer<'w> {
+    /// Stops game time for at least `duration` seconds.
+    pub fn freeze(&mut self, duration: f32) {
+        if self.freeze.resume_speed.is_none() {
+            self.freeze.resume_speed = Some(self.time.relative_speed());
+            self.time.set_relative_speed(0.);
+        }
+        self.freeze.remaining = self.freeze.remaining.max(duration);
+    }
+}
+
+pub fn trigger_impacts(
+    mut commands: Commands,
+    mut events: EventReader<DamageTakenEvent>,
+    sources: ImpactSources,
+    mut flashes: Query<&mut HitFlash>,
+    mut freezes: Query<&mut AnimationFreeze>,
+    mut time: TimeFreezer,
+    options: Res<ImpactOptions>,
+) {
+    let ImpactSources {
+        weapons,
+        responses,
+        transforms,
+        children,
+        sprites,
+    } = sources;
+
+    let default_weapon = WeaponImpact::default();
+    let default_response = ImpactResponse::default();
+
+    for event in events.iter() {
+        let weapon = event
+            .dealt_by
+            .and_then(|attacker| weapons.get(attacker).ok())
+            .unwrap_or(&default_weapon);
+        let response = responses.get(event.taken_by).unwrap_or(&default_response);
+        let target_hierarchy: Vec<_> = std::iter::once(event.taken_by)
+            .chain(children.iter_descendants(event.taken_by))
+            .collect();
+
+        if options.hit_flash {
+            for &entity in target_hierarchy.iter().filter(|&&e| sprites.contains(e)) {
+                let flash = HitFlash::new(response.flash_color, response.flash_duration);
+                match flashes.get_mut(entity) {
+                    // Restarts the running flash rather than racing its removal.
+                    Ok(mut running) => {
+                        running.color = flash.color;
+                        running.timer = flash.timer;
+                    }
+                    Err(_) => {
+                        commands.entity(entity).insert(flash);
+                    }
+                }
+            }
+        }
+
+        if options.hitstop {
+            let hitstop = if event.killing_blow {
+                weapon.killing_blow_hitstop
+            } else {
+                weapon.hitstop
+            } * response.hitstop_scale;
+
+            let attacker_hierarchy = event.dealt_by.into_iter().flat_map(|attacker| {
+                std::iter::once(attacker).chain(children.iter_descendants(attacker))
+            });
+            for entity in target_hierarchy.iter().copied().chain(attacker_hierarchy) {
+                match freezes.get_mut(entity) {
+                    Ok(mut freeze) => freeze.remaining = freeze.remaining.max(hitstop),
+                    Err(_) => {
+                        commands
+                            .entity(entity)
+                            .insert(AnimationFreeze { remaining: hitstop });
+                    }
+                }
+            }
+
+            if weapon.freeze_time && hitstop > 0. {
+                time.freeze(hitstop.min(options.max_time_freeze));
+            }
+        }
+
+        if options.particles {
+            let Ok(target) = transforms.get(event.taken_by) else {
+                continue;
+            };
+            let target = target.translation().truncate();
+            let towards_attacker = (event.from_position - target).normalize_or_zero();
+            let contact = target + towards_attacker * CONTACT_OFFSET;
+
+            let effects = [&weapon.sparks, &response.debris];
+            for effect in effects.into_iter().flatten() {
+                spawn_particle_effect(&mut commands, effect, contact, -towards_attacker);
+            }
+
+            if let Some(death) = response.death.as_ref().filter(|_| event.killing_blow) {
+                spawn_particle_effect(&mut commands, death, target, Vec2::Y);
+            }
+        }
+    }
+}
+
+/// Fades the flash layer of the sprite's [`SpriteColor`] out over the timer.
+pub fn apply_hit_flash(
+    mut commands: Commands,
+    mut flashes: Query<(Entity, &mut HitFlash, Option<&mut SpriteColor>)>,
+    time: Res<Time>,
+) {
+    for (entity, mut flash, sprite_color) in flashes.iter_mut() {
+        let finished = flash.timer.tick(time.delta()).finished();
+        if finished {
+            commands.entity(entity).remove::<HitFlash>();
+        }
+
+        let Some(mut sprite_color) = sprite_color else {
+            continue;
+        };
+
+        sprite_color.flash = if finished {
+            Color::NONE
+        } else {
+            let strength = flash.color.a() * flash.timer.percent_left();
+            flash.color.with_a(strength)
+        };
+    }
+}
+
+pub fn tick_time_freeze(mut time_freeze: ResMut<TimeFreeze>, mut time: ResMut<Time>) {
+    let Some(resume_speed) = time_freeze.resume_speed else {
+        return;
+    };
+
+    time_freeze.remaining -= time.raw_delta_seconds();
+    if time_freeze.remaining <= 0. {
+        time_freeze.remaining = 0.;
+        time_freeze.resume_speed = None;
+        time.set_relative_speed(resume_speed);
+    }
+}
@@ -0,0 +1,56 @@
+mod damage_numbers;
+mod environment;
+mod impact;
+
+use bevy::prelude::*;
+
+pub use damage_numbers::*;
+pub use environment::*;
+pub use impact::*;
+
+use crate::{
+    core::{DamageTakenEvent, GameplaySet},
+    player::PlayerMarker,
+    ActivePalette,
+};
+
+pub struct FxPlugin;
+
+impl Plugin for FxPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DamageNumberOptions>()
+            .init_resource::<DamageNumberOptions>()
+            .init_resource::<DamageNumberPool>()
+            .register_type::<ImpactOptions>()
+            .init_resource::<ImpactOptions>()
+            .register_type::<WeaponImpact>()
+            .register_type::<ImpactResponse>()
+            .init_resource::<TimeFreeze>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        recycle_damage_numbers.before(spawn_damage_numbers),
+                        spawn_damage_numbers,
+                        flash_palette_on_hero_damage,
+                        trigger_impacts,
+                        footstep_dust,
+                        grass_rustle,
+                    )
+                        .in_set(GameplaySet),
+                    apply_hit_flash.after(trigger_impacts),
+                    tick_time_freeze,
+                ),
+            );
+    }
+}
+
+pub fn flash_palette_on_hero_damage(
+    mut events: EventReader<DamageTakenEvent>,
+    mut palette: ResMut<ActivePalette>,
+    heroes: Query<(), With<PlayerMarker>>,
+) {
+    if events.iter().any(|event| heroes.contains(event.taken_by)) {
+        palette.flash("flash", 0.08);
+    }
+}
@@ -1,4 +1,11 @@
-use crate::player::*;
+use crate::{
+    core::HealthPool,
+    fx::{FootstepDust, WeaponImpact},
+    particles::ParticleEmitter,
+    player::*,
+    progression::Level,
+    AnimationLayer, AnimationLayers, Animator, SpriteName,
+};
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
@@ -7,6 +14,11 @@ pub struct HeroBundle {
     pub name: Name,
     pub marker: PlayerMarker,
     pub motor: PlayerMotor,
+    pub hp: HealthPool,
+    pub attack: AttackStats,
+    pub level: Level,
+    pub camera_target: CameraTarget,
+    pub impact: WeaponImpact,
 
     #[bundle()]
     pub visibility: VisibilityBundle,
@@ -28,6 +40,11 @@ impl Default for HeroBundle {
             visibility: Default::default(),
             marker: Default::default(),
             motor: Default::default(),
+            hp: HealthPool::new(10),
+            attack: Default::default(),
+            level: Default::default(),
+            camera_target: Default::default(),
+            impact: Default::default(),
             transform: Default::default(),
             rb: RigidBody::Dynamic,
             collider: Collider::ball(3.5),
@@ -37,3 +54,48 @@ impl Default for HeroBundle {
         }
     }
 }
+
+/// Spawns the hero with its sprite, weapon and effects.
+pub fn spawn_hero(commands: &mut Commands) -> Entity {
+    commands
+        .spawn((HeroBundle {
+            ..Default::default()
+        },))
+        .with_children(|hero| {
+            hero.spawn((
+                SpriteSheetBundle::default(),
+                SpriteName::new("player"),
+                PlayerSpriteMarker,
+                AnimationLayers::default(),
+                Animator::<PlayerSpriteAnimationState>::default()
+                    .with_layer(AnimationLayer::additive(0, 1.)),
+            ));
+
+            hero.spawn((
+                TransformBundle {
+                    local: Transform::from_xyz(0., 0., 0.),
+                    ..default()
+                },
+                VisibilityBundle::default(),
+                Animator::<WeaponAnimationState>::default(),
+            ))
+            .with_children(|pivot| {
+                pivot.spawn((
+                    SpriteSheetBundle {
+                        transform: Transform::from_xyz(0., 0., 1.),
+                        ..default()
+                    },
+                    SpriteName::new("sword"),
+                    PlayerWeaponMarker,
+                ));
+            });
+
+            hero.spawn((
+                Name::new("Footstep Dust"),
+                TransformBundle::from_transform(Transform::from_xyz(0., -3., 0.)),
+                ParticleEmitter::new("footstep_dust").with_active(false),
+                FootstepDust,
+            ));
+        })
+        .id()
+}
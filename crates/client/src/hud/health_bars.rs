@@ -0,0 +1,137 @@
+use bevy::{prelude::*, sprite::Anchor};
+
+use super::HudOptions;
+use crate::{core::HealthChangedEvent, player::PlayerMarker};
+
+/// Keeps bars above sprites, below damage numbers.
+const BAR_Z: f32 = 40.;
+
+/// A world-space bar above a damaged enemy, a child of the enemy.
+#[derive(Debug, Clone, Component)]
+pub struct HealthBar {
+    pub owner: Entity,
+    /// Seconds since the owner was last hurt.
+    pub shown_for: f32,
+    pub fraction: f32,
+}
+
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct HealthBarFill;
+
+fn bar_sprite(color: Color, size: Vec2, anchor: Anchor) -> Sprite {
+    Sprite {
+        color,
+        custom_size: Some(size),
+        anchor,
+        ..default()
+    }
+}
+
+pub fn show_health_bars(
+    mut commands: Commands,
+    mut events: EventReader<HealthChangedEvent>,
+    mut bars: Query<(&mut HealthBar, &mut Visibility, &Children)>,
+    mut fills: Query<&mut Transform, With<HealthBarFill>>,
+    heroes: Query<(), With<PlayerMarker>>,
+    owners: Query<(), With<GlobalTransform>>,
+    options: Res<HudOptions>,
+) {
+    for event in events.iter() {
+        if heroes.contains(event.entity) || !owners.contains(event.entity) {
+            continue;
+        }
+
+        let visible =
+            options.enemy_health_bars && event.current_hp > 0 && event.current_hp < event.max_hp;
+        let hurt = event.current_hp < event.previous_hp;
+        let fraction = event.fraction();
+
+        match bars.iter_mut().find(|(bar, ..)| bar.owner == event.entity) {
+            Some((mut bar, mut visibility, children)) => {
+                bar.fraction = fraction;
+                // Healing updates a bar that is still up but does not bring it back.
+                if !visible {
+                    *visibility = Visibility::Hidden;
+                } else if hurt {
+                    bar.shown_for = 0.;
+                    *visibility = Visibility::Inherited;
+                }
+                for &child in children.iter() {
+                    if let Ok(mut transform) = fills.get_mut(child) {
+                        transform.scale.x = fraction;
+                    }
+                }
+            }
+            None if visible => {
+                let size = options.enemy_bar_size;
+                let bar = commands
+                    .spawn((
+                        SpriteBundle {
+                            sprite: bar_sprite(options.health_background, size, Anchor::Center),
+                            transform: Transform::from_xyz(0., options.enemy_bar_offset, BAR_Z),
+                            ..default()
+                        },
+                        HealthBar {
+                            owner: event.entity,
+                            shown_for: 0.,
+                            fraction,
+                        },
+                        Name::new("Health Bar"),
+                    ))
+                    .with_children(|bar| {
+                        bar.spawn((
+                            SpriteBundle {
+                                sprite: bar_sprite(
+                                    options.enemy_health_color,
+                                    size,
+                                    Anchor::CenterLeft,
+                                ),
+                                transform: Transform::from_xyz(-size.x / 2., 0., 0.1)
+                                    .with_scale(Vec3::new(fraction, 1., 1.)),
+                                ..default()
+                            },
+                            HealthBarFill,
+                        ));
+                    })
+                    .id();
+                commands.entity(event.entity).add_child(bar);
+            }
+            None => {}
+        }
+    }
+}
+
+/// Fades bars out a while after their owner was last hurt.
+pub fn fade_health_bars(
+    mut bars: Query<(&mut HealthBar, &mut Sprite, &mut Visibility, &Children)>,
+    mut fills: Query<&mut Sprite, (With<HealthBarFill>, Without<HealthBar>)>,
+    time: Res<Time>,
+    options: Res<HudOptions>,
+) {
+    for (mut bar, mut sprite, mut visibility, children) in bars.iter_mut() {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+
+        bar.shown_for += time.delta_seconds();
+        let fading_for = bar.shown_for - options.enemy_bar_linger;
+        let alpha = if fading_for <= 0. {
+            1.
+        } else if options.enemy_bar_fade > 0. {
+            (1. - fading_for / options.enemy_bar_fade).max(0.)
+        } else {
+            0.
+        };
+
+        if alpha <= 0. {
+            *visibility = Visibility::Hidden;
+        }
+
+        sprite.color.set_a(alpha);
+        for &child in children.iter() {
+            if let Ok(mut fill) = fills.get_mut(child) {
+                fill.color.set_a(alpha);
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+use super::HudOptions;
+use crate::{
+    core::{HealthChangedEvent, HealthPool},
+    player::PlayerMarker,
+    state::GameState,
+};
+
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct HeroHealthHud;
+
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct HeroHealthText;
+
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct HeroHealthFill;
+
+fn health_text(current_hp: u32, max_hp: u32) -> String {
+    format!("HP {current_hp}/{max_hp}")
+}
+
+pub fn spawn_hero_health(mut commands: Commands, options: Res<HudOptions>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(16.),
+                    top: Val::Px(16.),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            HeroHealthHud,
+            Name::new("Hero Health"),
+        ))
+        .with_children(|hud| {
+            hud.spawn((
+                TextBundle::from_section(
+                    health_text(0, 0),
+                    TextStyle {
+                        font_size: 20.,
+                        color: options.text_color,
+                        ..default()
+                    },
+                ),
+                HeroHealthText,
+            ));
+
+            hud.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(160.),
+                    height: Val::Px(10.),
+                    ..default()
+                },
+                background_color: options.health_background.into(),
+                ..default()
+            })
+            .with_children(|bar| {
+                bar.spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(100.),
+                            height: Val::Percent(100.),
+                            ..default()
+                        },
+                        background_color: options.health_color.into(),
+                        ..default()
+                    },
+                    HeroHealthFill,
+                ));
+            });
+        });
+}
+
+/// Shows the hero's health whenever it changes, or when a hero appears.
+pub fn update_hero_health(
+    mut events: EventReader<HealthChangedEvent>,
+    spawned: Query<&HealthPool, (With<PlayerMarker>, Added<HealthPool>)>,
+    heroes: Query<(), With<PlayerMarker>>,
+    mut texts: Query<&mut Text, With<HeroHealthText>>,
+    mut fills: Query<&mut Style, With<HeroHealthFill>>,
+) {
+    let latest = events
+        .iter()
+        .filter(|event| heroes.contains(event.entity))
+        .map(|event| (event.current_hp, event.max_hp, event.fraction()))
+        .last()
+        .or_else(|| {
+            spawned
+                .iter()
+                .next()
+                .map(|hp| (hp.current_hp, hp.max_hp, hp.fraction()))
+        });
+
+    let Some((current_hp, max_hp, fraction)) = latest else {
+        return;
+    };
+
+    for mut text in texts.iter_mut() {
+        if let Some(section) = text.sections.first_mut() {
+            section.value = health_text(current_hp, max_hp);
+        }
+    }
+    for mut style in fills.iter_mut() {
+        style.width = Val::Percent(fraction * 100.);
+    }
+}
+
+/// Only shows the hero's health while a run is going on.
+pub fn show_hero_health(
+    state: Res<State<GameState>>,
+    mut huds: Query<&mut Visibility, With<HeroHealthHud>>,
+) {
+    for mut visibility in huds.iter_mut() {
+        *visibility = match state.get() {
+            GameState::MainMenu => Visibility::Hidden,
+            _ => Visibility::Inherited,
+        };
+    }
+}
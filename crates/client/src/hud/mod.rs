@@ -0,0 +1,69 @@
+mod health_bars;
+mod hero_health;
+
+use bevy::prelude::*;
+use bevy_inspector_egui::{prelude::ReflectInspectorOptions, InspectorOptions};
+
+pub use health_bars::*;
+pub use hero_health::*;
+
+use crate::{
+    core::{damage_system, GameplaySet},
+    state::GameState,
+};
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<HudOptions>()
+            .init_resource::<HudOptions>()
+            .add_systems(Startup, spawn_hero_health)
+            .add_systems(
+                Update,
+                (
+                    update_hero_health.after(damage_system),
+                    show_hero_health.run_if(state_changed::<GameState>()),
+                    (
+                        show_health_bars.after(damage_system),
+                        fade_health_bars.after(show_health_bars),
+                    )
+                        .in_set(GameplaySet),
+                ),
+            );
+    }
+}
+
+#[derive(Resource, Reflect, InspectorOptions)]
+#[reflect(Resource, InspectorOptions)]
+pub struct HudOptions {
+    pub health_color: Color,
+    pub health_background: Color,
+    pub text_color: Color,
+    /// Shows bars above enemies that are missing health.
+    pub enemy_health_bars: bool,
+    pub enemy_health_color: Color,
+    /// Size of enemy health bars in world pixels.
+    pub enemy_bar_size: Vec2,
+    /// Pixels above an enemy's origin its bar sits at.
+    pub enemy_bar_offset: f32,
+    /// Seconds a bar stays after the last change before fading out.
+    pub enemy_bar_linger: f32,
+    pub enemy_bar_fade: f32,
+}
+
+impl Default for HudOptions {
+    fn default() -> Self {
+        Self {
+            health_color: Color::rgb_u8(0xD9, 0x3F, 0x4A),
+            health_background: Color::rgb_u8(0x2D, 0x29, 0x1C),
+            text_color: Color::rgb_u8(0xC8, 0xAC, 0x93),
+            enemy_health_bars: true,
+            enemy_health_color: Color::rgb_u8(0xB1, 0x74, 0x3D),
+            enemy_bar_size: Vec2::new(12., 2.),
+            enemy_bar_offset: 9.,
+            enemy_bar_linger: 2.,
+            enemy_bar_fade: 0.5,
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    math::ivec2,
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::{BoxedFuture, HashMap, HashSet},
+};
+use serde::Deserialize;
+
+use super::{chunk_bundle, chunk_of, tile_bundle, TileGrid, TileKind, TileMap, TileSprites};
+use crate::{catalog_changed, PaletteColor, SpriteCatalog, SpriteLibrary, Sprites};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Neighbour {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Neighbour {
+    pub fn offset(&self) -> IVec2 {
+        match self {
+            Self::N => ivec2(0, 1),
+            Self::NE => ivec2(1, 1),
+            Self::E => ivec2(1, 0),
+            Self::SE => ivec2(1, -1),
+            Self::S => ivec2(0, -1),
+            Self::SW => ivec2(-1, -1),
+            Self::W => ivec2(-1, 0),
+            Self::NW => ivec2(-1, 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct AutotileSprite {
+    pub index: usize,
+    #[serde(default)]
+    pub flip_x: bool,
+    #[serde(default)]
+    pub flip_y: bool,
+}
+
+/// Matches when every neighbour in `all` holds the same terrain and
+/// none of the neighbours in `none` do.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AutotileRule {
+    #[serde(default)]
+    pub all: Vec<Neighbour>,
+    #[serde(default)]
+    pub none: Vec<Neighbour>,
+    pub sprite: AutotileSprite,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TerrainRules {
+    pub default: AutotileSprite,
+    #[serde(default)]
+    pub rules: Vec<AutotileRule>,
+}
+
+#[derive(Debug, Deserialize, TypeUuid, TypePath)]
+#[uuid = "a3e1f0b2-5c7d-4e8f-9a1b-2c3d4e5f6a70"]
+pub struct AutotileRules {
+    pub terrains: HashMap<TileKind, TerrainRules>,
+}
+
+impl AutotileRules {
+    pub fn resolve(
+        &self,
+        grid: &TileGrid,
+        position: IVec2,
+        tile: TileKind,
+    ) -> Option<AutotileSprite> {
+        let terrain = self.terrains.get(&tile)?;
+        let is_same = |neighbour: &Neighbour| grid.get(position + neighbour.offset()) == Some(tile);
+
+        let sprite = terrain
+            .rules
+            .iter()
+            .find(|rule| rule.all.iter().all(is_same) && !rule.none.iter().any(is_same))
+            .map(|rule| rule.sprite)
+            .unwrap_or(terrain.default);
+
+        Some(sprite)
+    }
+}
+
+/// Picks the sprite of a tile, falling back to the tile's catalog sprite
+/// when no rules are loaded for its terrain.
+pub fn tile_sprite(
+    grid: &TileGrid,
+    position: IVec2,
+    tile: TileKind,
+    rules: Option<&AutotileRules>,
+    sprites: &Sprites,
+) -> TextureAtlasSprite {
+    let mut sprite = sprites.sprite(tile.sprite_name());
+
+    if let Some(resolved) = rules.and_then(|rules| rules.resolve(grid, position, tile)) {
+        sprite.index = resolved.index;
+        sprite.flip_x = resolved.flip_x;
+        sprite.flip_y = resolved.flip_y;
+    }
+
+    sprite
+}
+
+#[derive(Default)]
+pub struct AutotileRulesLoader;
+
+impl AssetLoader for AutotileRulesLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let rules = ron::de::from_bytes::<AutotileRules>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(rules));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["autotile.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct AutotileLibrary {
+    pub rules: Handle<AutotileRules>,
+}
+
+impl FromWorld for AutotileLibrary {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            rules: world.resource::<AssetServer>().load("terrain.autotile.ron"),
+        }
+    }
+}
+
+pub fn refresh_autotiles_on_reload(
+    mut maps: Query<&mut TileMap>,
+    mut events: EventReader<AssetEvent<AutotileRules>>,
+    mut sprite_events: EventReader<AssetEvent<SpriteCatalog>>,
+    library: Res<AutotileLibrary>,
+    sprite_library: Res<SpriteLibrary>,
+) {
+    let rules_reloaded = events.iter().any(|event| match event {
+        AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+            *handle == library.rules
+        }
+        AssetEvent::Removed { .. } => false,
+    });
+    let sprites_reloaded = catalog_changed(&mut sprite_events, &sprite_library.catalog);
+
+    if rules_reloaded || sprites_reloaded {
+        for mut map in maps.iter_mut() {
+            map.mark_all_dirty();
+        }
+    }
+}
+
+/// Changed cells and their neighbours, whose sprites may have to change.
+fn affected_cells(grid: &TileGrid, dirty: Vec<IVec2>) -> HashSet<IVec2> {
+    dirty
+        .into_iter()
+        .flat_map(|cell| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dx| cell + ivec2(dx, dy))))
+        .filter(|cell| grid.contains(*cell))
+        .collect()
+}
+
+/// Re-resolves every changed tile together with its neighbours, spawning,
+/// updating or despawning sprites as needed.
+pub fn update_autotiles(
+    mut commands: Commands,
+    mut maps: Query<(Entity, &mut TileMap, &mut TileSprites), Changed<TileMap>>,
+    mut tiles: Query<(
+        &mut TextureAtlasSprite,
+        &mut Handle<TextureAtlas>,
+        &mut PaletteColor,
+    )>,
+    library: Res<AutotileLibrary>,
+    all_rules: Res<Assets<AutotileRules>>,
+    sprites: Sprites,
+) {
+    let rules = all_rules.get(&library.rules);
+
+    for (root, mut map, mut tile_sprites) in maps.iter_mut() {
+        let dirty = map.bypass_change_detection().take_dirty();
+        if dirty.is_empty() {
+            continue;
+        }
+
+        for cell in affected_cells(&map, dirty) {
+            let existing = tile_sprites.tiles.get(&cell).copied();
+
+            match (map.get(cell), existing) {
+                (None, Some(entity)) => {
+                    commands.entity(entity).despawn_recursive();
+                    tile_sprites.tiles.remove(&cell);
+                }
+                (Some(tile), Some(entity)) => {
+                    if let Ok((mut sprite, mut atlas, mut color)) = tiles.get_mut(entity) {
+                        *sprite = tile_sprite(&map, cell, tile, rules, &sprites);
+                        *atlas = sprites.atlas(tile.sprite_name());
+                        color.set_if_neq(sprites.palette_color(tile.sprite_name()));
+                    }
+                }
+                (Some(tile), None) => {
+                    let chunk = chunk_of(cell);
+                    let chunk_entity = *tile_sprites.chunks.entry(chunk).or_insert_with(|| {
+                        let chunk_entity = commands.spawn(chunk_bundle(chunk)).id();
+                        commands.entity(root).add_child(chunk_entity);
+                        chunk_entity
+                    });
+
+                    let sprite = tile_sprite(&map, cell, tile, rules, &sprites);
+                    let atlas = sprites.atlas(tile.sprite_name());
+                    let tile_entity = commands
+                        .spawn((
+                            tile_bundle(&atlas, cell, sprite),
+                            sprites.palette_color(tile.sprite_name()),
+                        ))
+                        .id();
+                    commands.entity(chunk_entity).add_child(tile_entity);
+                    tile_sprites.tiles.insert(cell, tile_entity);
+                }
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprite(index: usize) -> AutotileSprite {
+        AutotileSprite {
+            index,
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+
+    fn walls(cells: &[IVec2]) -> TileGrid {
+        let mut grid = TileGrid::new(UVec2::splat(5));
+        for cell in cells {
+            grid.set(*cell, Some(TileKind::Wall));
+        }
+        grid
+    }
+
+    #[test]
+    fn resolve_picks_the_first_matching_rule() {
+        let rules = AutotileRules {
+            terrains: HashMap::from_iter([(
+                TileKind::Wall,
+                TerrainRules {
+                    default: sprite(0),
+                    rules: vec![
+                        AutotileRule {
+                            all: vec![Neighbour::N, Neighbour::S],
+                            none: vec![Neighbour::E],
+                            sprite: sprite(1),
+                        },
+                        AutotileRule {
+                            all: vec![Neighbour::N],
+                            none: vec![],
+                            sprite: sprite(2),
+                        },
+                    ],
+                },
+            )]),
+        };
+        let resolve = |grid: &TileGrid| {
+            rules
+                .resolve(grid, ivec2(2, 2), TileKind::Wall)
+                .map(|sprite| sprite.index)
+        };
+
+        let column = [ivec2(2, 1), ivec2(2, 2), ivec2(2, 3)];
+        assert_eq!(resolve(&walls(&column)), Some(1));
+        assert_eq!(
+            resolve(&walls(&[column.as_slice(), &[ivec2(3, 2)]].concat())),
+            Some(2)
+        );
+        assert_eq!(resolve(&walls(&[ivec2(2, 2), ivec2(2, 3)])), Some(2));
+        assert_eq!(resolve(&walls(&[ivec2(2, 2)])), Some(0));
+        assert!(rules
+            .resolve(&walls(&[]), ivec2(2, 2), TileKind::Grass)
+            .is_none());
+    }
+
+    #[test]
+    fn changed_tile_only_affects_its_neighbourhood() {
+        let mut map = TileMap::new(walls(&[]));
+        map.set_tile(ivec2(2, 2), Some(TileKind::Wall));
+        map.set_tile(ivec2(2, 2), Some(TileKind::Wall));
+
+        let dirty = map.take_dirty();
+        assert_eq!(dirty, vec![ivec2(2, 2)]);
+        let expected: HashSet<IVec2> = (1..=3)
+            .flat_map(|y| (1..=3).map(move |x| ivec2(x, y)))
+            .collect();
+        assert_eq!(affected_cells(&map, dirty), expected);
+
+        // Cells outside the grid are left out.
+        map.set_tile(ivec2(0, 0), Some(TileKind::Rock));
+        let dirty = map.take_dirty();
+        assert_eq!(affected_cells(&map, dirty).len(), 4);
+        assert!(map.take_dirty().is_empty());
+    }
+}
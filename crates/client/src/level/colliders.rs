@@ -0,0 +1,194 @@
+use bevy::{math::ivec2, prelude::*, utils::HashMap};
+use bevy_rapier2d::prelude::*;
+
+use super::{TileGrid, TileMap};
+use crate::TILE_SIZE;
+
+/// Holds the outline collider for all solid tiles of its parent [`TileMap`],
+/// along with the solid cells it was traced from.
+#[derive(Debug, Default, Clone, Component)]
+pub struct TileColliders {
+    solid: Vec<bool>,
+}
+
+fn solid_cells(grid: &TileGrid) -> Vec<bool> {
+    let size = grid.size.as_ivec2();
+    (0..size.y)
+        .flat_map(|y| (0..size.x).map(move |x| ivec2(x, y)))
+        .map(|cell| grid.get(cell).is_some_and(|tile| tile.is_solid()))
+        .collect()
+}
+
+/// Traces the boundary between solid and open cells, treating everything
+/// outside the grid as open. Collinear edges are joined, so segments only
+/// end where the outline turns. Returns the ends of every segment as cell
+/// corners.
+pub fn solid_outline(grid: &TileGrid) -> Vec<(IVec2, IVec2)> {
+    let size = grid.size.as_ivec2();
+    let is_solid = |cell: IVec2| grid.get(cell).is_some_and(|tile| tile.is_solid());
+
+    let mut segments = vec![];
+    let mut trace = |lines: i32, length: i32, corner: fn(i32, i32) -> IVec2| {
+        for line in 0..=lines {
+            // Start of the current edge and whether the solid side comes first.
+            let mut run: Option<(i32, bool)> = None;
+            for at in 0..=length {
+                let before = is_solid(corner(line - 1, at));
+                let after = is_solid(corner(line, at));
+                let edge = (before != after).then_some(before);
+
+                if run.map(|(_, side)| side) != edge {
+                    if let Some((start, _)) = run {
+                        segments.push((corner(line, start), corner(line, at)));
+                    }
+                    run = edge.map(|side| (at, side));
+                }
+            }
+        }
+    };
+
+    trace(size.y, size.x, |y, x| ivec2(x, y));
+    trace(size.x, size.y, ivec2);
+
+    segments
+}
+
+/// Builds a single polyline from the outline, sharing the corners between
+/// the segments meeting there.
+fn outline_collider(segments: &[(IVec2, IVec2)]) -> Collider {
+    let mut vertices = vec![];
+    let mut corners = HashMap::new();
+    let indices = segments
+        .iter()
+        .map(|(start, end)| {
+            [*start, *end].map(|corner| {
+                *corners.entry(corner).or_insert_with(|| {
+                    vertices.push(corner.as_vec2() * TILE_SIZE);
+                    vertices.len() as u32 - 1
+                })
+            })
+        })
+        .collect();
+
+    Collider::polyline(vertices, Some(indices))
+}
+
+/// Retraces the colliders of changed tile maps, as long as their solid
+/// cells differ from the ones the current colliders were traced from.
+pub fn rebuild_tile_colliders(
+    mut commands: Commands,
+    maps: Query<(Entity, &TileMap, Option<&Children>), Changed<TileMap>>,
+    colliders: Query<&TileColliders>,
+) {
+    for (entity, map, children) in maps.iter() {
+        let solid = solid_cells(map);
+
+        let mut up_to_date = false;
+        for child in children.iter().flat_map(|children| children.iter()) {
+            let Ok(colliders) = colliders.get(*child) else {
+                continue;
+            };
+
+            if colliders.solid == solid {
+                up_to_date = true;
+            } else {
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+
+        if up_to_date {
+            continue;
+        }
+
+        let segments = solid_outline(map);
+        commands.entity(entity).with_children(|level| {
+            let mut colliders = level.spawn((
+                Name::new("Tile Colliders"),
+                TileColliders { solid },
+                TransformBundle::default(),
+            ));
+
+            if !segments.is_empty() {
+                colliders.insert((RigidBody::Fixed, outline_collider(&segments)));
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::TileKind;
+
+    fn grid(rows: &[&str]) -> TileGrid {
+        let size = UVec2::new(rows[0].len() as u32, rows.len() as u32);
+        let mut grid = TileGrid::new(size);
+        for (y, row) in rows.iter().rev().enumerate() {
+            for (x, cell) in row.chars().enumerate() {
+                let tile = (cell == '#').then_some(TileKind::Wall);
+                grid.set(ivec2(x as i32, y as i32), tile);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn outline_joins_collinear_edges() {
+        let mut outline = solid_outline(&grid(&["....", ".##.", "...."]));
+        outline.sort_by_key(|(start, end)| (start.x, start.y, end.x, end.y));
+        assert_eq!(
+            outline,
+            vec![
+                (ivec2(1, 1), ivec2(1, 2)),
+                (ivec2(1, 1), ivec2(3, 1)),
+                (ivec2(1, 2), ivec2(3, 2)),
+                (ivec2(3, 1), ivec2(3, 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn outline_skips_edges_between_solid_cells() {
+        let outline = solid_outline(&grid(&["###", "###", "###"]));
+        assert_eq!(outline.len(), 4);
+        assert!(solid_outline(&grid(&["...", "..."])).is_empty());
+    }
+
+    #[test]
+    fn colliders_rebuild_when_walls_change() {
+        let mut app = App::new();
+        app.add_systems(Update, rebuild_tile_colliders);
+        let level = app.world.spawn(TileMap::new(grid(&["#..", "..."]))).id();
+
+        let colliders = |app: &mut App| {
+            app.update();
+            app.world
+                .query::<(Entity, &TileColliders, &Parent)>()
+                .iter(&app.world)
+                .map(|(entity, colliders, parent)| {
+                    assert_eq!(parent.get(), level);
+                    (
+                        entity,
+                        colliders.solid.iter().filter(|solid| **solid).count(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let traced = colliders(&mut app);
+        assert_eq!(traced.len(), 1);
+        assert_eq!(traced[0].1, 1);
+
+        // Open tiles do not touch the colliders.
+        let mut map = app.world.get_mut::<TileMap>(level).unwrap();
+        map.set_tile(ivec2(2, 0), Some(TileKind::Grass));
+        assert_eq!(colliders(&mut app), traced);
+
+        let mut map = app.world.get_mut::<TileMap>(level).unwrap();
+        map.set_tile(ivec2(2, 0), Some(TileKind::Wall));
+        let rebuilt = colliders(&mut app);
+        assert_eq!(rebuilt.len(), 1);
+        assert_ne!(rebuilt[0].0, traced[0].0);
+        assert_eq!(rebuilt[0].1, 2);
+    }
+}
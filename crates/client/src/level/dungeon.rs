@@ -0,0 +1,393 @@
+use std::collections::VecDeque;
+
+use bevy::{math::ivec2, prelude::*};
+use bevy_inspector_egui::{prelude::ReflectInspectorOptions, InspectorOptions};
+
+use super::{LevelLayout, LevelObject, LevelObjectKind, TileKind};
+use crate::TILE_SIZE;
+
+/// Smallest dungeon that still fits a room with separate start and exit
+/// cells and the walls around it. Smaller sizes are raised to it.
+pub const MIN_DUNGEON_SIZE: u32 = 6;
+
+#[derive(Debug, Clone, Resource, Reflect, InspectorOptions)]
+#[reflect(Resource, InspectorOptions)]
+pub struct DungeonSettings {
+    pub size: UVec2,
+    pub min_leaf_size: i32,
+    pub min_room_size: i32,
+    pub grass_density: f32,
+    pub rock_density: f32,
+    pub spawner_chance: f32,
+    pub chest_chance: f32,
+}
+
+impl Default for DungeonSettings {
+    fn default() -> Self {
+        Self {
+            size: UVec2::new(48, 48),
+            min_leaf_size: 10,
+            min_room_size: 4,
+            grass_density: 0.04,
+            rock_density: 0.015,
+            spawner_chance: 0.5,
+            chest_chance: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Area {
+    min: IVec2,
+    size: IVec2,
+}
+
+impl Area {
+    fn center(&self) -> IVec2 {
+        self.min + self.size / 2
+    }
+
+    fn cells(&self) -> impl Iterator<Item = IVec2> + '_ {
+        (0..self.size.y).flat_map(move |y| (0..self.size.x).map(move |x| self.min + ivec2(x, y)))
+    }
+}
+
+struct Carver<'a> {
+    settings: &'a DungeonSettings,
+    rng: fastrand::Rng,
+    floor: Vec<bool>,
+    rooms: Vec<Area>,
+}
+
+impl Carver<'_> {
+    fn index_of(&self, cell: IVec2) -> usize {
+        (cell.y * self.settings.size.x as i32 + cell.x) as usize
+    }
+
+    fn is_floor(&self, cell: IVec2) -> bool {
+        cell.x >= 0
+            && cell.y >= 0
+            && cell.x < self.settings.size.x as i32
+            && cell.y < self.settings.size.y as i32
+            && self.floor[self.index_of(cell)]
+    }
+
+    fn dig(&mut self, cell: IVec2) {
+        let idx = self.index_of(cell);
+        self.floor[idx] = true;
+    }
+
+    /// Recursively splits `area` and returns a cell inside one of its rooms,
+    /// which the caller connects to the sibling partition.
+    fn partition(&mut self, area: Area) -> IVec2 {
+        let min_leaf = self.settings.min_leaf_size.max(4);
+        let can_split_x = area.size.x >= min_leaf * 2;
+        let can_split_y = area.size.y >= min_leaf * 2;
+
+        let split_x = match (can_split_x, can_split_y) {
+            (false, false) => return self.place_room(area),
+            (true, true) => self.rng.bool(),
+            (split_x, _) => split_x,
+        };
+
+        let (first, second) = if split_x {
+            let at = self.rng.i32(min_leaf..=area.size.x - min_leaf);
+            (
+                Area {
+                    min: area.min,
+                    size: ivec2(at, area.size.y),
+                },
+                Area {
+                    min: area.min + ivec2(at, 0),
+                    size: ivec2(area.size.x - at, area.size.y),
+                },
+            )
+        } else {
+            let at = self.rng.i32(min_leaf..=area.size.y - min_leaf);
+            (
+                Area {
+                    min: area.min,
+                    size: ivec2(area.size.x, at),
+                },
+                Area {
+                    min: area.min + ivec2(0, at),
+                    size: ivec2(area.size.x, area.size.y - at),
+                },
+            )
+        };
+
+        let from = self.partition(first);
+        let to = self.partition(second);
+        self.dig_corridor(from, to);
+
+        if self.rng.bool() {
+            from
+        } else {
+            to
+        }
+    }
+
+    fn place_room(&mut self, leaf: Area) -> IVec2 {
+        let max_size = (leaf.size - 2).max(IVec2::ONE);
+        let min_size = IVec2::splat(self.settings.min_room_size).min(max_size);
+        let size = ivec2(
+            self.rng.i32(min_size.x..=max_size.x),
+            self.rng.i32(min_size.y..=max_size.y),
+        );
+        let min = leaf.min
+            + IVec2::ONE
+            + ivec2(
+                self.rng.i32(0..=max_size.x - size.x),
+                self.rng.i32(0..=max_size.y - size.y),
+            );
+
+        let room = Area { min, size };
+        for cell in room.cells() {
+            self.dig(cell);
+        }
+        self.rooms.push(room);
+
+        room.center()
+    }
+
+    fn dig_corridor(&mut self, from: IVec2, to: IVec2) {
+        let corner = if self.rng.bool() {
+            ivec2(to.x, from.y)
+        } else {
+            ivec2(from.x, to.y)
+        };
+
+        for (start, end) in [(from, corner), (corner, to)] {
+            let step = (end - start).signum();
+            let mut cell = start;
+            self.dig(cell);
+            while cell != end {
+                cell += step;
+                self.dig(cell);
+            }
+        }
+    }
+
+    /// Walking distance from `start` to every floor cell, `None` if unreachable.
+    fn distances_from(&self, start: IVec2) -> Vec<Option<u32>> {
+        let mut distances = vec![None; self.floor.len()];
+        let mut queue = VecDeque::from([start]);
+        distances[self.index_of(start)] = Some(0);
+
+        while let Some(cell) = queue.pop_front() {
+            let distance = distances[self.index_of(cell)].unwrap_or_default();
+            for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                let next = cell + offset;
+                if self.is_floor(next) && distances[self.index_of(next)].is_none() {
+                    distances[self.index_of(next)] = Some(distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        distances
+    }
+}
+
+fn object_at(kind: LevelObjectKind, cell: IVec2) -> LevelObject {
+    LevelObject {
+        kind,
+        position: (cell.as_vec2() + 0.5) * TILE_SIZE,
+    }
+}
+
+fn carve(seed: u64, settings: &DungeonSettings) -> Carver<'_> {
+    let mut carver = Carver {
+        settings,
+        rng: fastrand::Rng::with_seed(seed),
+        floor: vec![false; (settings.size.x * settings.size.y) as usize],
+        rooms: vec![],
+    };
+
+    carver.partition(Area {
+        min: IVec2::ONE,
+        size: settings.size.as_ivec2() - 2,
+    });
+
+    carver
+}
+
+/// Generates a dungeon of rooms joined by corridors. The output depends only
+/// on `seed` and `settings`, every room is reachable from the hero start and
+/// the exit is placed in the room farthest away from it.
+pub fn generate_dungeon(seed: u64, settings: &DungeonSettings) -> LevelLayout {
+    let settings = &DungeonSettings {
+        size: settings.size.max(UVec2::splat(MIN_DUNGEON_SIZE)),
+        min_room_size: settings.min_room_size.max(2),
+        ..settings.clone()
+    };
+    let mut carver = carve(seed, settings);
+
+    let mut layout = LevelLayout::new(settings.size);
+
+    for y in 0..settings.size.y as i32 {
+        for x in 0..settings.size.x as i32 {
+            let cell = ivec2(x, y);
+            if carver.is_floor(cell) {
+                continue;
+            }
+
+            let borders_floor = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| ivec2(dx, dy)))
+                .any(|offset| carver.is_floor(cell + offset));
+            if borders_floor {
+                layout.tiles.set(cell, Some(TileKind::Wall));
+            }
+        }
+    }
+
+    let start = carver.rooms[0].center();
+    let distances = carver.distances_from(start);
+    let distance_to = |cell: &IVec2| distances[carver.index_of(*cell)];
+    // With a single room the exit goes to its cell farthest from the start,
+    // rooms always hold more than one cell.
+    let exit = carver
+        .rooms
+        .iter()
+        .map(Area::center)
+        .max_by_key(distance_to)
+        .filter(|exit| *exit != start)
+        .or_else(|| carver.rooms[0].cells().max_by_key(distance_to))
+        .unwrap_or(start);
+
+    layout
+        .objects
+        .push(object_at(LevelObjectKind::HeroStart, start));
+    layout.objects.push(object_at(LevelObjectKind::Exit, exit));
+
+    let rooms = carver.rooms.clone();
+    for room in rooms.iter().skip(1) {
+        let cells: Vec<IVec2> = room.cells().filter(|cell| *cell != room.center()).collect();
+
+        if carver.rng.f32() < settings.spawner_chance && room.center() != exit {
+            layout
+                .objects
+                .push(object_at(LevelObjectKind::Spawner, room.center()));
+        }
+
+        if carver.rng.f32() < settings.chest_chance && !cells.is_empty() {
+            let cell = cells[carver.rng.usize(..cells.len())];
+            layout.objects.push(object_at(LevelObjectKind::Chest, cell));
+        }
+    }
+
+    let occupied: Vec<IVec2> = layout
+        .objects
+        .iter()
+        .map(|object| (object.position / TILE_SIZE).floor().as_ivec2())
+        .collect();
+
+    for room in rooms.iter() {
+        for cell in room.cells() {
+            if occupied.contains(&cell) {
+                continue;
+            }
+
+            let roll = carver.rng.f32();
+            if roll < settings.grass_density {
+                layout.tiles.set(cell, Some(TileKind::Grass));
+            } else if roll < settings.grass_density + settings.rock_density {
+                layout.tiles.set(cell, Some(TileKind::Rock));
+            }
+        }
+    }
+
+    layout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEEDS: [u64; 4] = [0, 1, 0xDEAD_BEEF, u64::MAX];
+
+    #[test]
+    fn same_seed_generates_same_dungeon() {
+        let settings = DungeonSettings::default();
+        for seed in SEEDS {
+            let first = generate_dungeon(seed, &settings);
+            let second = generate_dungeon(seed, &settings);
+            assert_eq!(first.tiles, second.tiles);
+            assert_eq!(first.objects, second.objects);
+        }
+    }
+
+    fn position_of(layout: &LevelLayout, kind: LevelObjectKind) -> Option<Vec2> {
+        layout
+            .objects
+            .iter()
+            .find(|object| object.kind == kind)
+            .map(|object| object.position)
+    }
+
+    #[test]
+    fn exit_is_away_from_the_start() {
+        let settings = DungeonSettings::default();
+        for seed in SEEDS {
+            let layout = generate_dungeon(seed, &settings);
+            let start = position_of(&layout, LevelObjectKind::HeroStart);
+            let exit = position_of(&layout, LevelObjectKind::Exit);
+            assert!(start.is_some() && exit.is_some(), "seed {seed}");
+            assert_ne!(start, exit, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn every_floor_cell_is_reachable() {
+        let settings = DungeonSettings::default();
+        for seed in SEEDS {
+            let carver = carve(seed, &settings);
+            let distances = carver.distances_from(carver.rooms[0].center());
+            for (floor, distance) in carver.floor.iter().zip(distances) {
+                assert_eq!(*floor, distance.is_some(), "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn rooms_stay_inside_the_walls() {
+        let settings = DungeonSettings::default();
+        let max = settings.size.as_ivec2() - 1;
+        for seed in SEEDS {
+            let carver = carve(seed, &settings);
+            assert!(!carver.rooms.is_empty());
+            for room in carver.rooms.iter() {
+                assert!(room.size.cmpge(IVec2::ONE).all(), "{room:?}");
+                assert!(room.min.cmpge(IVec2::ONE).all(), "{room:?}");
+                assert!((room.min + room.size).cmple(max).all(), "{room:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn tiny_settings_are_clamped() {
+        for size in [
+            UVec2::ZERO,
+            UVec2::ONE,
+            UVec2::new(3, 40),
+            UVec2::new(40, 2),
+        ] {
+            let settings = DungeonSettings {
+                size,
+                min_leaf_size: 0,
+                min_room_size: 0,
+                ..default()
+            };
+            let layout = generate_dungeon(7, &settings);
+            assert!(layout
+                .tiles
+                .size
+                .cmpge(UVec2::splat(MIN_DUNGEON_SIZE))
+                .all());
+            let start = position_of(&layout, LevelObjectKind::HeroStart);
+            let exit = position_of(&layout, LevelObjectKind::Exit);
+            assert!(start.is_some() && exit.is_some(), "{size}");
+            assert_ne!(start, exit, "{size}");
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::TILE_SIZE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Deserialize)]
+pub enum TileKind {
+    Grass,
+    Rock,
+    Wall,
+}
+
+impl TileKind {
+    pub fn sprite_name(&self) -> &'static str {
+        match self {
+            Self::Grass => "grass",
+            Self::Rock => "rock",
+            Self::Wall => "wall",
+        }
+    }
+
+    pub fn is_solid(&self) -> bool {
+        matches!(self, Self::Wall)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum LevelObjectKind {
+    HeroStart,
+    Exit,
+    Dummy,
+    Spawner,
+    Chest,
+}
+
+impl LevelObjectKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "hero_start" => Some(Self::HeroStart),
+            "exit" => Some(Self::Exit),
+            "dummy" => Some(Self::Dummy),
+            "spawner" => Some(Self::Spawner),
+            "chest" => Some(Self::Chest),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelObject {
+    pub kind: LevelObjectKind,
+    /// Position in pixels relative to the bottom left corner of the level.
+    pub position: Vec2,
+}
+
+/// Grid coordinates grow right and up, starting in the bottom left corner.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TileGrid {
+    pub size: UVec2,
+    tiles: Vec<Option<TileKind>>,
+}
+
+impl TileGrid {
+    pub fn new(size: UVec2) -> Self {
+        Self {
+            size,
+            tiles: vec![None; (size.x * size.y) as usize],
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, TileKind)> + '_ {
+        self.tiles.iter().enumerate().filter_map(|(i, tile)| {
+            let position = IVec2::new(i as i32 % self.size.x as i32, i as i32 / self.size.x as i32);
+            tile.map(|tile| (position, tile))
+        })
+    }
+
+    pub fn contains(&self, position: IVec2) -> bool {
+        position.x >= 0
+            && position.y >= 0
+            && position.x < self.size.x as i32
+            && position.y < self.size.y as i32
+    }
+
+    pub fn get(&self, position: IVec2) -> Option<TileKind> {
+        self.contains(position)
+            .then(|| self.tiles[self.index_of(position)])
+            .flatten()
+    }
+
+    pub fn set(&mut self, position: IVec2, tile: Option<TileKind>) {
+        if self.contains(position) {
+            let idx = self.index_of(position);
+            self.tiles[idx] = tile;
+        }
+    }
+
+    /// World-space offset of the bottom left corner, keeping the level centered.
+    pub fn origin(&self) -> Vec2 {
+        -self.size.as_vec2() * TILE_SIZE / 2.
+    }
+
+    fn index_of(&self, position: IVec2) -> usize {
+        (position.y as u32 * self.size.x + position.x as u32) as usize
+    }
+}
+
+/// Source-agnostic description of a level, produced by the loaders.
+#[derive(Debug, Clone, Default)]
+pub struct LevelLayout {
+    pub tiles: TileGrid,
+    pub objects: Vec<LevelObject>,
+}
+
+impl LevelLayout {
+    pub fn new(size: UVec2) -> Self {
+        Self {
+            tiles: TileGrid::new(size),
+            objects: vec![],
+        }
+    }
+}
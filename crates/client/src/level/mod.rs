@@ -0,0 +1,199 @@
+mod autotile;
+mod colliders;
+mod dungeon;
+mod layout;
+mod run;
+mod spawn;
+mod tiled;
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+};
+
+pub use autotile::*;
+pub use colliders::*;
+pub use dungeon::*;
+pub use layout::*;
+pub use run::*;
+pub use spawn::*;
+pub use tiled::*;
+
+use crate::{
+    build_sprite_atlases,
+    content::tick_dummy_spawners,
+    core::GameplaySet,
+    player::{CameraBounds, CameraScript, CameraScriptEvent, CameraShot, PlayerMarker},
+    state::GameState,
+    Ease, Sprites, TILE_SIZE,
+};
+
+/// Level every run starts on.
+pub const START_LEVEL: &str = "levels/sandbox.tmj";
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DungeonSettings>()
+            .register_type::<DungeonRun>()
+            .init_resource::<DungeonSettings>()
+            .init_resource::<DungeonRun>()
+            .add_asset::<LevelAsset>()
+            .init_asset_loader::<TiledLevelLoader>()
+            .init_resource::<CurrentLevel>()
+            .add_asset::<AutotileRules>()
+            .init_asset_loader::<AutotileRulesLoader>()
+            .init_resource::<AutotileLibrary>()
+            .add_systems(
+                Update,
+                (
+                    (descend_through_exit, tick_dummy_spawners).in_set(GameplaySet),
+                    spawn_level
+                        .after(descend_through_exit)
+                        .after(build_sprite_atlases)
+                        .run_if(in_state(GameState::Playing)),
+                    rebuild_tile_colliders,
+                    refresh_autotiles_on_reload.before(update_autotiles),
+                    update_autotiles,
+                    update_camera_bounds,
+                ),
+            );
+    }
+}
+
+#[derive(Debug, TypeUuid, TypePath)]
+#[uuid = "0c4b5d0e-2a8f-4d8e-b3a1-6e2b4f9d7c21"]
+pub struct LevelAsset {
+    pub layout: LevelLayout,
+}
+
+#[derive(Resource)]
+pub struct CurrentLevel {
+    pub handle: Handle<LevelAsset>,
+}
+
+impl CurrentLevel {
+    pub fn start(asset_server: &AssetServer) -> Self {
+        Self {
+            handle: asset_server.load(START_LEVEL),
+        }
+    }
+}
+
+impl FromWorld for CurrentLevel {
+    fn from_world(world: &mut World) -> Self {
+        Self::start(world.resource::<AssetServer>())
+    }
+}
+
+/// The current level along with everything its tiles are built from.
+#[derive(SystemParam)]
+pub struct LevelAssets<'w> {
+    current: Res<'w, CurrentLevel>,
+    levels: Res<'w, Assets<LevelAsset>>,
+    autotile: Res<'w, Assets<AutotileRules>>,
+    autotile_library: Res<'w, AutotileLibrary>,
+    sprites: Sprites<'w>,
+}
+
+pub fn spawn_level(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<LevelAsset>>,
+    mut heroes: Query<&mut Transform, With<PlayerMarker>>,
+    mut camera_scripts: EventWriter<CameraScriptEvent>,
+    mut spawned_from: Local<Option<Handle<LevelAsset>>>,
+    mut spawned: Query<(Entity, Option<&mut TileMap>), With<LevelEntity>>,
+    assets: LevelAssets,
+) {
+    let current = &assets.current.handle;
+    let modified = events
+        .iter()
+        .any(|event| matches!(event, AssetEvent::Modified { handle } if handle == current));
+
+    // Also respawns the level after a restart despawned it.
+    if !modified && spawned_from.as_ref() == Some(current) && !spawned.is_empty() {
+        return;
+    }
+
+    let Some(level) = assets
+        .levels
+        .get(current)
+        .filter(|_| assets.sprites.is_loaded())
+    else {
+        return;
+    };
+
+    // Hot reloads of a level that kept its size only update the changed tiles.
+    let kept_map = match spawned
+        .iter_mut()
+        .find_map(|(entity, map)| Some((entity, map?)))
+    {
+        Some((entity, mut map)) if modified && map.size == level.layout.tiles.size => {
+            map.update_from(&level.layout.tiles);
+            Some(entity)
+        }
+        _ => None,
+    };
+
+    for (entity, _) in spawned
+        .iter()
+        .filter(|(entity, _)| Some(*entity) != kept_map)
+    {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    match kept_map {
+        Some(_) => spawn_level_objects(&mut commands, &level.layout, &mut heroes),
+        None => {
+            spawn_level_layout(
+                &mut commands,
+                &level.layout,
+                &assets.sprites,
+                assets.autotile.get(&assets.autotile_library.rules),
+                &mut heroes,
+            );
+        }
+    }
+    // Hot reloads keep the camera where it is.
+    if let Some(script) = intro_script(&level.layout).filter(|_| !modified) {
+        camera_scripts.send(CameraScriptEvent::Play(script));
+    }
+    *spawned_from = Some(current.clone());
+}
+
+/// Shows where the exit is before panning back to the hero start.
+fn intro_script(layout: &LevelLayout) -> Option<CameraScript> {
+    let find = |kind| {
+        layout
+            .objects
+            .iter()
+            .find(|object| object.kind == kind)
+            .map(|object| layout.tiles.origin() + object.position)
+    };
+    let exit = find(LevelObjectKind::Exit)?;
+    let start = find(LevelObjectKind::HeroStart)?;
+
+    Some(
+        CameraScript::new([
+            CameraShot::focus(exit, 1.).with_zoom(3.),
+            CameraShot::rail([exit, start], 1.5, Ease::SineInOut).with_zoom(3.),
+        ])
+        .with_blend(0.75, Ease::QuadOut),
+    )
+}
+
+/// Keeps the camera inside the current level.
+pub fn update_camera_bounds(
+    maps: Query<&TileMap, Changed<TileMap>>,
+    mut bounds: ResMut<CameraBounds>,
+) {
+    for map in maps.iter() {
+        let origin = map.origin();
+        bounds.rect = Some(Rect::from_corners(
+            origin,
+            origin + map.size.as_vec2() * TILE_SIZE,
+        ));
+    }
+}
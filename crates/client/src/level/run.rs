@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use super::{generate_dungeon, CurrentLevel, DungeonSettings, LevelAsset};
+use crate::{content::LevelExit, player::PlayerMarker, TILE_SIZE};
+
+#[derive(Debug, Clone, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct DungeonRun {
+    pub seed: u64,
+    pub floor: u32,
+}
+
+impl Default for DungeonRun {
+    fn default() -> Self {
+        Self {
+            seed: fastrand::u64(..),
+            floor: 0,
+        }
+    }
+}
+
+impl DungeonRun {
+    pub fn floor_seed(&self) -> u64 {
+        self.seed ^ (self.floor as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
+
+    /// The level of the current floor, generated from the run's seed past
+    /// the starting level.
+    pub fn floor_level(
+        &self,
+        settings: &DungeonSettings,
+        asset_server: &AssetServer,
+        levels: &mut Assets<LevelAsset>,
+    ) -> CurrentLevel {
+        if self.floor == 0 {
+            return CurrentLevel::start(asset_server);
+        }
+
+        CurrentLevel {
+            handle: levels.add(LevelAsset {
+                layout: generate_dungeon(self.floor_seed(), settings),
+            }),
+        }
+    }
+}
+
+pub fn descend_through_exit(
+    heroes: Query<&GlobalTransform, With<PlayerMarker>>,
+    exits: Query<&GlobalTransform, With<LevelExit>>,
+    mut run: ResMut<DungeonRun>,
+    mut current: ResMut<CurrentLevel>,
+    mut levels: ResMut<Assets<LevelAsset>>,
+    settings: Res<DungeonSettings>,
+    asset_server: Res<AssetServer>,
+) {
+    let reached_exit = heroes.iter().any(|hero| {
+        exits.iter().any(|exit| {
+            hero.translation()
+                .truncate()
+                .distance(exit.translation().truncate())
+                < TILE_SIZE / 2.
+        })
+    });
+
+    if !reached_exit {
+        return;
+    }
+
+    run.floor += 1;
+    info!("Descending to floor {} of run {:#x}", run.floor, run.seed);
+
+    *current = run.floor_level(&settings, &asset_server, &mut levels);
+}
@@ -0,0 +1,193 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{tile_sprite, AutotileRules, LevelLayout, LevelObjectKind, TileGrid, TileKind};
+use crate::{
+    content::{spawn_dummy, ChestBundle, DummySpawnerBundle, LevelExitBundle},
+    player::PlayerMarker,
+    Sprites, TILE_SIZE,
+};
+
+pub const CHUNK_SIZE: i32 = 16;
+const TILE_Z: f32 = -1.;
+
+/// Runtime copy of the level's tiles, stored on the level root. Tiles are
+/// changed through [`TileMap::set_tile`] so dependent systems know which
+/// cells to update.
+#[derive(Debug, Clone, Component)]
+pub struct TileMap {
+    grid: TileGrid,
+    dirty: Vec<IVec2>,
+}
+
+impl TileMap {
+    pub fn new(grid: TileGrid) -> Self {
+        Self {
+            grid,
+            dirty: vec![],
+        }
+    }
+
+    pub fn set_tile(&mut self, position: IVec2, tile: Option<TileKind>) {
+        if self.grid.contains(position) && self.grid.get(position) != tile {
+            self.grid.set(position, tile);
+            self.dirty.push(position);
+        }
+    }
+
+    /// Copies the tiles of `grid` over, only marking the ones that differ.
+    pub fn update_from(&mut self, grid: &TileGrid) {
+        let size = self.grid.size.as_ivec2();
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let position = IVec2::new(x, y);
+                self.set_tile(position, grid.get(position));
+            }
+        }
+    }
+
+    pub fn mark_all_dirty(&mut self) {
+        let size = self.grid.size.as_ivec2();
+        self.dirty = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| IVec2::new(x, y)))
+            .collect();
+    }
+
+    pub fn take_dirty(&mut self) -> Vec<IVec2> {
+        std::mem::take(&mut self.dirty)
+    }
+}
+
+impl std::ops::Deref for TileMap {
+    type Target = TileGrid;
+
+    fn deref(&self) -> &Self::Target {
+        &self.grid
+    }
+}
+
+/// Sprite and chunk entities of a [`TileMap`], keyed by grid and chunk position.
+#[derive(Debug, Default, Clone, Component)]
+pub struct TileSprites {
+    pub tiles: HashMap<IVec2, Entity>,
+    pub chunks: HashMap<IVec2, Entity>,
+}
+
+/// Everything spawned from a level, despawned when the level is reloaded.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct LevelEntity;
+
+pub fn chunk_of(position: IVec2) -> IVec2 {
+    position.div_euclid(IVec2::splat(CHUNK_SIZE))
+}
+
+pub fn chunk_bundle(chunk: IVec2) -> impl Bundle {
+    (
+        Name::new(format!("Chunk {chunk}")),
+        SpatialBundle::from_transform(Transform::from_translation(
+            ((chunk * CHUNK_SIZE).as_vec2() * TILE_SIZE).extend(0.),
+        )),
+    )
+}
+
+pub fn tile_bundle(
+    texture_atlas: &Handle<TextureAtlas>,
+    position: IVec2,
+    sprite: TextureAtlasSprite,
+) -> SpriteSheetBundle {
+    let offset = ((position - chunk_of(position) * CHUNK_SIZE).as_vec2() + 0.5) * TILE_SIZE;
+
+    SpriteSheetBundle {
+        texture_atlas: texture_atlas.clone(),
+        sprite,
+        transform: Transform::from_translation(offset.extend(0.)),
+        ..default()
+    }
+}
+
+pub fn spawn_level_layout(
+    commands: &mut Commands,
+    layout: &LevelLayout,
+    sprites: &Sprites,
+    autotile: Option<&AutotileRules>,
+    heroes: &mut Query<&mut Transform, With<PlayerMarker>>,
+) -> Entity {
+    let origin = layout.tiles.origin();
+
+    let mut chunks: HashMap<IVec2, Vec<(IVec2, TileKind)>> = HashMap::new();
+    for (position, tile) in layout.tiles.iter() {
+        chunks
+            .entry(chunk_of(position))
+            .or_default()
+            .push((position, tile));
+    }
+
+    let mut tile_sprites = TileSprites::default();
+    let root = commands
+        .spawn((
+            Name::new("Level"),
+            LevelEntity,
+            TileMap::new(layout.tiles.clone()),
+            SpatialBundle::from_transform(Transform::from_translation(origin.extend(TILE_Z))),
+        ))
+        .with_children(|root| {
+            for (chunk, tiles) in chunks.into_iter() {
+                let chunk_entity = root
+                    .spawn(chunk_bundle(chunk))
+                    .with_children(|chunk| {
+                        for (position, tile) in tiles {
+                            let sprite =
+                                tile_sprite(&layout.tiles, position, tile, autotile, sprites);
+                            let atlas = sprites.atlas(tile.sprite_name());
+                            let tile_entity = chunk.spawn((
+                                tile_bundle(&atlas, position, sprite),
+                                sprites.palette_color(tile.sprite_name()),
+                            ));
+                            tile_sprites.tiles.insert(position, tile_entity.id());
+                        }
+                    })
+                    .id();
+
+                tile_sprites.chunks.insert(chunk, chunk_entity);
+            }
+        })
+        .id();
+
+    commands.entity(root).insert(tile_sprites);
+    spawn_level_objects(commands, layout, heroes);
+
+    root
+}
+
+/// Spawns the objects of a level and moves the heroes to its start.
+pub fn spawn_level_objects(
+    commands: &mut Commands,
+    layout: &LevelLayout,
+    heroes: &mut Query<&mut Transform, With<PlayerMarker>>,
+) {
+    let origin = layout.tiles.origin();
+
+    for object in layout.objects.iter() {
+        let transform = Transform::from_translation((origin + object.position).extend(0.));
+
+        match object.kind {
+            LevelObjectKind::HeroStart => {
+                for mut hero in heroes.iter_mut() {
+                    hero.translation = transform.translation;
+                }
+            }
+            LevelObjectKind::Exit => {
+                commands.spawn((LevelExitBundle::new(transform), LevelEntity));
+            }
+            LevelObjectKind::Dummy => {
+                let dummy = spawn_dummy(commands, transform);
+                commands.entity(dummy).insert(LevelEntity);
+            }
+            LevelObjectKind::Spawner => {
+                commands.spawn((DummySpawnerBundle::new(transform), LevelEntity));
+            }
+            LevelObjectKind::Chest => {
+                commands.spawn((ChestBundle::new(transform), LevelEntity));
+            }
+        }
+    }
+}
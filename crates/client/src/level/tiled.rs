@@ -0,0 +1,162 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    math::{ivec2, uvec2, vec2},
+    prelude::*,
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+
+use super::{LevelAsset, LevelLayout, LevelObject, LevelObjectKind, TileKind};
+use crate::{SpriteCatalog, SPRITE_CATALOG_PATH};
+
+/// Atlas of the sprite catalog the tiles of a map are painted with.
+const TILED_ATLAS: &str = "tileset";
+
+/// The top bits of a gid hold the flip and rotation flags.
+const GID_FLAGS_MASK: u32 = 0xF0000000;
+
+#[derive(Debug, Deserialize)]
+struct TiledMap {
+    width: u32,
+    height: u32,
+    tilewidth: u32,
+    tileheight: u32,
+    layers: Vec<TiledLayer>,
+    tilesets: Vec<TiledTileset>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum TiledLayer {
+    TileLayer {
+        #[serde(default)]
+        data: Vec<u32>,
+        width: u32,
+        #[serde(default)]
+        x: i32,
+        #[serde(default)]
+        y: i32,
+    },
+    ObjectGroup {
+        #[serde(default)]
+        objects: Vec<TiledObject>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct TiledObject {
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    ty: String,
+    #[serde(default)]
+    class: String,
+    x: f32,
+    y: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct TiledTileset {
+    firstgid: u32,
+}
+
+impl TiledMap {
+    fn atlas_index(&self, gid: u32) -> Option<usize> {
+        let gid = gid & !GID_FLAGS_MASK;
+        if gid == 0 {
+            return None;
+        }
+
+        let firstgid = self
+            .tilesets
+            .iter()
+            .map(|tileset| tileset.firstgid)
+            .filter(|firstgid| *firstgid <= gid)
+            .max()?;
+
+        Some((gid - firstgid) as usize)
+    }
+
+    /// Builds the layout, resolving painted tiles through the `kind` of the
+    /// catalog sprite at their atlas index.
+    fn into_layout(self, kinds: &HashMap<usize, TileKind>) -> LevelLayout {
+        let mut layout = LevelLayout::new(uvec2(self.width, self.height));
+        let map_height = (self.height * self.tileheight) as f32;
+        let tile_scale = vec2(
+            crate::TILE_SIZE / self.tilewidth as f32,
+            crate::TILE_SIZE / self.tileheight as f32,
+        );
+
+        for layer in self.layers.iter() {
+            match layer {
+                TiledLayer::TileLayer { data, width, x, y } => {
+                    for (i, gid) in data.iter().enumerate() {
+                        let column = x + (i as u32 % width) as i32;
+                        let row = y + (i as u32 / width) as i32;
+                        let position = ivec2(column, self.height as i32 - 1 - row);
+
+                        let Some(index) = self.atlas_index(*gid) else {
+                            continue;
+                        };
+
+                        match kinds.get(&index) {
+                            Some(kind) => layout.tiles.set(position, Some(*kind)),
+                            None => warn!("Unknown tile with atlas index {index}, skipping"),
+                        }
+                    }
+                }
+                TiledLayer::ObjectGroup { objects } => {
+                    for object in objects.iter() {
+                        let kind = [&object.class, &object.ty, &object.name]
+                            .into_iter()
+                            .find_map(|name| LevelObjectKind::from_name(name));
+
+                        let Some(kind) = kind else {
+                            warn!("Unknown object {:?}, skipping", object.name);
+                            continue;
+                        };
+
+                        layout.objects.push(LevelObject {
+                            kind,
+                            position: vec2(object.x, map_height - object.y) * tile_scale,
+                        });
+                    }
+                }
+                TiledLayer::Other => {}
+            }
+        }
+
+        layout
+    }
+}
+
+#[derive(Default)]
+pub struct TiledLevelLoader;
+
+impl AssetLoader for TiledLevelLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let map = serde_json::from_slice::<TiledMap>(bytes)?;
+            let catalog = load_context.read_asset_bytes(SPRITE_CATALOG_PATH).await?;
+            let kinds = ron::de::from_bytes::<SpriteCatalog>(&catalog)?.tile_kinds(TILED_ATLAS);
+
+            load_context.set_default_asset(
+                LoadedAsset::new(LevelAsset {
+                    layout: map.into_layout(&kinds),
+                })
+                .with_dependency(SPRITE_CATALOG_PATH.into()),
+            );
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tmj"]
+    }
+}
@@ -1,132 +1,49 @@
 #![feature(trivial_bounds)]
 
-use core::CorePlugin;
+use console::ConsolePlugin;
+use core::{CorePlugin, GameplaySet};
 
 use bevy::{prelude::*, window::PresentMode};
 use bevy_inspector_egui::{quick::WorldInspectorPlugin, DefaultInspectorConfigPlugin};
 use bevy_rapier2d::prelude::*;
-use content::{
-    dummy_damage_shake, tick_dummy_sprite, DummyAnimationState, DummyBodyBundle, DummySpriteBundle,
-};
-use fx::damage_numbers;
-use hero::HeroBundle;
+use content::{dummy_damage_shake, tick_dummy_sprite, DummyAnimationState};
+use fx::FxPlugin;
+use hud::HudPlugin;
+use level::LevelPlugin;
+use particles::ParticlePlugin;
 use player::{
     CameraBundle, CameraPlugin, CombatPlugin, PlayerAnimatorPlugin, PlayerLocomotionPlugin,
-    PlayerSpriteMarker, PlayerWeaponMarker, WeaponAnimationState,
 };
+use progression::ProgressionPlugin;
+use save::SavePlugin;
+use settings::SettingsPlugin;
+use sound::SoundPlugin;
+use state::GameStatePlugin;
 
 mod animation;
+mod console;
 mod content;
 mod core;
 mod fx;
 mod hero;
+mod hud;
+mod level;
+mod palette;
+mod particles;
 mod player;
+mod progression;
+mod save;
+mod settings;
+mod sound;
+mod state;
 mod tileset;
 
 pub use animation::*;
+pub use palette::*;
 pub use tileset::*;
 
-fn setup(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut atlases: ResMut<Assets<TextureAtlas>>,
-) {
-    let texture_handle = asset_server.load("tileset.png");
-    let texture_atlas =
-        TextureAtlas::from_grid(texture_handle, Vec2::new(8.0, 8.0), 16, 16, None, None);
-    let texture_atlas = atlases.add(texture_atlas);
-
+fn setup(mut commands: Commands) {
     commands.spawn(CameraBundle::default());
-
-    commands.spawn(SpriteSheetBundle {
-        texture_atlas: texture_atlas.clone(),
-        sprite: GRASS.clone(),
-        transform: Transform::from_xyz(-16., 8., 0.),
-        ..default()
-    });
-    commands.spawn(SpriteSheetBundle {
-        texture_atlas: texture_atlas.clone(),
-        sprite: GRASS.clone(),
-        transform: Transform::from_xyz(12., 4., 0.),
-        ..default()
-    });
-    commands.spawn(SpriteSheetBundle {
-        texture_atlas: texture_atlas.clone(),
-        sprite: GRASS.clone(),
-        transform: Transform::from_xyz(4., -8., 0.),
-        ..default()
-    });
-    commands.spawn(SpriteSheetBundle {
-        texture_atlas: texture_atlas.clone(),
-        sprite: GRASS.clone(),
-        transform: Transform::from_xyz(-4., -12., 0.),
-        ..default()
-    });
-
-    commands.spawn(SpriteSheetBundle {
-        texture_atlas: texture_atlas.clone(),
-        sprite: ROCK.clone(),
-        transform: Transform::from_xyz(-8., 12., 0.),
-        ..default()
-    });
-
-    commands.spawn((
-        SpriteSheetBundle {
-            texture_atlas: texture_atlas.clone(),
-            sprite: WALL.clone(),
-            transform: Transform::from_xyz(16., 16., 0.),
-            ..default()
-        },
-        RigidBody::Fixed,
-        Collider::cuboid(4., 4.),
-    ));
-
-    commands
-        .spawn(DummyBodyBundle::default())
-        .with_children(|parent| {
-            parent.spawn(DummySpriteBundle::new(texture_atlas.clone()));
-        });
-
-    commands
-        .spawn((HeroBundle {
-            ..Default::default()
-        },))
-        .with_children(|hero| {
-            hero.spawn((
-                SpriteSheetBundle {
-                    texture_atlas: texture_atlas.clone(),
-                    sprite: PLAYER.clone(),
-                    ..default()
-                },
-                PlayerSpriteMarker,
-            ));
-
-            hero.spawn((
-                TransformBundle {
-                    local: Transform::from_xyz(0., 0., 0.),
-                    ..default()
-                },
-                VisibilityBundle::default(),
-                Animator::<WeaponAnimationState>::default(),
-            ))
-            .with_children(|pivot| {
-                pivot.spawn((
-                    SpriteSheetBundle {
-                        texture_atlas,
-                        sprite: SWORD.clone(),
-                        transform: Transform::from_xyz(0., 0., 1.),
-                        ..default()
-                    },
-                    PlayerWeaponMarker,
-                ));
-            });
-        });
-}
-
-pub fn toggle_debug_render_context(mut ctx: ResMut<DebugRenderContext>, keys: Res<Input<KeyCode>>) {
-    if keys.just_pressed(KeyCode::Slash) {
-        ctx.enabled = !ctx.enabled;
-    }
 }
 
 use wasm_bindgen::prelude::*;
@@ -143,6 +60,13 @@ pub fn start() {
         .add_plugins(
             DefaultPlugins
                 .set(ImagePlugin::default_nearest())
+                .set(AssetPlugin {
+                    #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+                    watch_for_changes: bevy::asset::ChangeWatcher::with_delay(
+                        std::time::Duration::from_millis(200),
+                    ),
+                    ..default()
+                })
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         present_mode: PresentMode::AutoNoVsync,
@@ -154,12 +78,28 @@ pub fn start() {
         )
         // game related stuff
         .add_plugins((
+            GameStatePlugin,
+            PalettePlugin,
+            TilesetPlugin,
+            AnimatorCorePlugin,
+            AnimatorPlugin::<DummyAnimationState>::default(),
             CameraPlugin,
             PlayerAnimatorPlugin,
             PlayerLocomotionPlugin,
             CombatPlugin,
             CorePlugin,
         ))
+        .add_plugins((
+            FxPlugin,
+            ParticlePlugin,
+            ProgressionPlugin,
+            LevelPlugin,
+            SoundPlugin,
+            HudPlugin,
+            SavePlugin,
+            SettingsPlugin,
+            ConsolePlugin,
+        ))
         // physics
         .register_type::<RigidBody>()
         .insert_resource(RapierConfiguration {
@@ -176,13 +116,7 @@ pub fn start() {
         .add_systems(Startup, setup)
         .add_systems(
             Update,
-            (
-                toggle_debug_render_context,
-                damage_numbers,
-                dummy_damage_shake,
-                tick_dummy_sprite,
-                animator_system::<DummyAnimationState>,
-            ),
+            (dummy_damage_shake, tick_dummy_sprite).in_set(GameplaySet),
         )
         // cool gui stuff
         .add_plugins(DefaultInspectorConfigPlugin)
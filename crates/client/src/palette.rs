@@ -0,0 +1,252 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+
+use crate::AnimationSet;
+
+pub struct PalettePlugin;
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ActivePalette>()
+            .register_type::<PaletteColor>()
+            .init_resource::<ActivePalette>()
+            .add_asset::<PaletteSet>()
+            .init_asset_loader::<PaletteSetLoader>()
+            .init_resource::<PaletteLibrary>()
+            .add_systems(Update, (tick_palette_flash, apply_palette).chain())
+            .add_systems(
+                PostUpdate,
+                compose_sprite_colors.after(AnimationSet::Animate),
+            );
+    }
+}
+
+/// Palette slot used for the window's clear colour.
+pub const BACKGROUND_SLOT: &str = "background";
+
+/// Every palette maps slot names to hex colours such as `"646C5E"`.
+#[derive(Debug, Deserialize, TypeUuid, TypePath)]
+#[uuid = "9c5e7a12-4b3d-4f8e-a1c6-0d2b8e7f5a94"]
+pub struct PaletteSet {
+    pub palettes: HashMap<String, HashMap<String, String>>,
+}
+
+#[derive(Default)]
+pub struct PaletteSetLoader;
+
+impl AssetLoader for PaletteSetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let set = ron::de::from_bytes::<PaletteSet>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(set));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["palettes.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct PaletteLibrary {
+    pub set: Handle<PaletteSet>,
+}
+
+impl FromWorld for PaletteLibrary {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            set: world.resource::<AssetServer>().load("default.palettes.ron"),
+        }
+    }
+}
+
+/// The palette sprites are currently tinted with. A flash temporarily
+/// overrides it, e.g. to blink the whole screen when the hero is hit.
+#[derive(Debug, Clone, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct ActivePalette {
+    pub name: String,
+    pub flash: Option<(String, Timer)>,
+}
+
+impl Default for ActivePalette {
+    fn default() -> Self {
+        Self {
+            name: "default".into(),
+            flash: None,
+        }
+    }
+}
+
+impl ActivePalette {
+    pub fn current(&self) -> &str {
+        self.flash
+            .as_ref()
+            .map(|(name, _)| name.as_str())
+            .unwrap_or(&self.name)
+    }
+
+    pub fn flash(&mut self, palette: impl Into<String>, duration: f32) {
+        self.flash = Some((
+            palette.into(),
+            Timer::from_seconds(duration, TimerMode::Once),
+        ));
+    }
+}
+
+/// Tints the entity's [`TextureAtlasSprite`] with a slot of the active palette.
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+pub struct PaletteColor {
+    pub slot: String,
+    pub shade: f32,
+}
+
+impl PaletteColor {
+    pub fn new(slot: impl Into<String>, shade: f32) -> Self {
+        Self {
+            slot: slot.into(),
+            shade,
+        }
+    }
+}
+
+impl Default for PaletteColor {
+    fn default() -> Self {
+        Self::new("", 1.)
+    }
+}
+
+/// Colour layers of a sprite, combined into its [`TextureAtlasSprite`]
+/// colour by [`compose_sprite_colors`]. Every system writes its own layer
+/// so palette swaps, clip tints and hit flashes do not undo each other.
+/// Added to every entity with a [`PaletteColor`].
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct SpriteColor {
+    /// Resolved from the entity's [`PaletteColor`].
+    pub base: Color,
+    /// Multiplied with the base, e.g. by animation clips.
+    pub tint: Color,
+    /// Mixed over the tinted colour by its alpha, e.g. by hit flashes.
+    pub flash: Color,
+}
+
+impl SpriteColor {
+    pub fn new(base: Color) -> Self {
+        Self {
+            base,
+            tint: Color::WHITE,
+            flash: Color::NONE,
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        let tinted =
+            Vec4::from_array(self.base.as_rgba_f32()) * Vec4::from_array(self.tint.as_rgba_f32());
+        let [r, g, b, amount] = self.flash.as_rgba_f32();
+        let [r, g, b] = tinted
+            .truncate()
+            .lerp(Vec3::new(r, g, b), amount)
+            .to_array();
+        Color::rgba(r, g, b, tinted.w)
+    }
+}
+
+impl Default for SpriteColor {
+    fn default() -> Self {
+        Self::new(Color::WHITE)
+    }
+}
+
+#[derive(SystemParam)]
+pub struct Palettes<'w> {
+    library: Res<'w, PaletteLibrary>,
+    sets: Res<'w, Assets<PaletteSet>>,
+    active: Res<'w, ActivePalette>,
+}
+
+impl<'w> Palettes<'w> {
+    pub fn get(&self, slot: &str) -> Option<Color> {
+        self.sets
+            .get(&self.library.set)
+            .and_then(|set| set.palettes.get(self.active.current()))
+            .and_then(|palette| palette.get(slot))
+            .and_then(|hex| Color::hex(hex).ok())
+    }
+
+    /// Resolves `color` against the active palette. Unknown slots are white.
+    pub fn resolve(&self, color: &PaletteColor) -> Color {
+        let base = self.get(&color.slot).unwrap_or(Color::WHITE);
+        let [r, g, b, a] = base.as_rgba_f32();
+        Color::rgba(r * color.shade, g * color.shade, b * color.shade, a)
+    }
+
+    pub fn has_changed(&self, events: &mut EventReader<AssetEvent<PaletteSet>>) -> bool {
+        let reloaded = events.iter().any(|event| match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                *handle == self.library.set
+            }
+            AssetEvent::Removed { .. } => false,
+        });
+
+        reloaded || self.active.is_changed()
+    }
+}
+
+pub fn tick_palette_flash(mut active: ResMut<ActivePalette>, time: Res<Time>) {
+    let finished = match active.bypass_change_detection().flash.as_mut() {
+        Some((_, timer)) => timer.tick(time.delta()).finished(),
+        None => false,
+    };
+
+    if finished {
+        active.flash = None;
+    }
+}
+
+pub fn apply_palette(
+    mut commands: Commands,
+    mut tinted: Query<(Entity, Ref<PaletteColor>, Option<&mut SpriteColor>)>,
+    mut events: EventReader<AssetEvent<PaletteSet>>,
+    mut clear_color: ResMut<ClearColor>,
+    palettes: Palettes,
+) {
+    let refresh_all = palettes.has_changed(&mut events);
+
+    if let Some(background) = palettes.get(BACKGROUND_SLOT).filter(|_| refresh_all) {
+        clear_color.0 = background;
+    }
+
+    for (entity, color, sprite_color) in tinted.iter_mut() {
+        if !refresh_all && !color.is_changed() {
+            continue;
+        }
+
+        let base = palettes.resolve(&color);
+        match sprite_color {
+            Some(mut sprite_color) => sprite_color.base = base,
+            None => {
+                commands.entity(entity).insert(SpriteColor::new(base));
+            }
+        }
+    }
+}
+
+pub fn compose_sprite_colors(
+    mut sprites: Query<(&SpriteColor, &mut TextureAtlasSprite), Changed<SpriteColor>>,
+) {
+    for (sprite_color, mut sprite) in sprites.iter_mut() {
+        sprite.color = sprite_color.color();
+    }
+}
@@ -0,0 +1,159 @@
+use std::{f32::consts::TAU, sync::Arc};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+
+use crate::{Curve, HexColor};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect, Deserialize)]
+pub enum ParticleSpace {
+    /// Particles stay where they were emitted.
+    #[default]
+    World,
+    /// Particles are parented to the emitter and move along with it.
+    Local,
+}
+
+fn full_circle() -> f32 {
+    TAU
+}
+
+fn one() -> f32 {
+    1.
+}
+
+/// How an emitter spawns particles and how they behave over their life.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParticleEffect {
+    /// Particles per second while the emitter runs.
+    #[serde(default)]
+    pub rate: f32,
+    /// Particles spawned at once when the emitter starts.
+    #[serde(default)]
+    pub burst: u32,
+    /// Seconds the emitter runs. Without a duration, emitters with a rate run
+    /// until removed and the others stop after their burst.
+    #[serde(default)]
+    pub duration: Option<f32>,
+    /// Seconds, picked between the two for every particle.
+    pub lifetime: (f32, f32),
+    /// Pixels per second, picked between the two for every particle.
+    #[serde(default)]
+    pub speed: (f32, f32),
+    /// Width in radians of the cone particles fly out in, around the
+    /// emitter's direction.
+    #[serde(default = "full_circle")]
+    pub spread: f32,
+    /// Pixels around the emitter particles appear in.
+    #[serde(default)]
+    pub radius: f32,
+    /// Pixels per second squared pulling particles down the screen, negative
+    /// values make them rise.
+    #[serde(default)]
+    pub gravity: f32,
+    /// Fraction of velocity lost per second.
+    #[serde(default)]
+    pub drag: f32,
+    /// Depth relative to the emitter.
+    #[serde(default)]
+    pub z: f32,
+    #[serde(default)]
+    pub space: ParticleSpace,
+    /// Sprite from the catalog, a plain square of `size` pixels when `None`.
+    #[serde(default)]
+    pub sprite: Option<String>,
+    #[serde(default = "one")]
+    pub size: f32,
+    /// Scale over the particle's life, keyed from 0 to 1.
+    #[serde(default)]
+    pub scale: Curve<f32>,
+    /// Colour over the particle's life, keyed from 0 to 1. Sprites keep
+    /// their palette colour when empty.
+    #[serde(default)]
+    pub color: Curve<HexColor>,
+}
+
+impl ParticleEffect {
+    /// Whether an emitter that has been running for `elapsed` seconds is done.
+    pub fn is_finished(&self, elapsed: f32, burst_done: bool) -> bool {
+        match self.duration {
+            Some(duration) => elapsed >= duration,
+            None => self.rate <= 0. && burst_done,
+        }
+    }
+}
+
+/// Effects authored in a `.particles.ron` file, keyed by name.
+#[derive(Debug, TypeUuid, TypePath)]
+#[uuid = "a3c7e2f1-6b4d-4f8a-9e51-2d7b08c4f6a9"]
+pub struct ParticleEffects {
+    pub effects: HashMap<String, Arc<ParticleEffect>>,
+}
+
+#[derive(Deserialize)]
+struct ParticleEffectsDef {
+    effects: HashMap<String, ParticleEffect>,
+}
+
+#[derive(Default)]
+pub struct ParticleEffectsLoader;
+
+impl AssetLoader for ParticleEffectsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let def = ron::de::from_bytes::<ParticleEffectsDef>(bytes)?;
+            let effects = def
+                .effects
+                .into_iter()
+                .map(|(name, effect)| (name, Arc::new(effect)))
+                .collect();
+            load_context.set_default_asset(LoadedAsset::new(ParticleEffects { effects }));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["particles.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct ParticleLibrary {
+    pub effects: Handle<ParticleEffects>,
+}
+
+impl FromWorld for ParticleLibrary {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            effects: world
+                .resource::<AssetServer>()
+                .load("default.particles.ron"),
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct Particles<'w> {
+    library: Res<'w, ParticleLibrary>,
+    effects: Res<'w, Assets<ParticleEffects>>,
+}
+
+impl<'w> Particles<'w> {
+    /// The named effect, `None` while effects load or when it does not exist.
+    pub fn effect(&self, name: &str) -> Option<Arc<ParticleEffect>> {
+        self.effects
+            .get(&self.library.effects)
+            .and_then(|effects| effects.effects.get(name))
+            .cloned()
+    }
+}
@@ -0,0 +1,171 @@
+use std::{f32::consts::TAU, sync::Arc};
+
+use bevy::prelude::*;
+
+use super::{Particle, ParticleEffect, ParticleSpace, Particles};
+use crate::Sprites;
+
+/// Spawns particles of a named effect from the entity's position.
+#[derive(Debug, Clone, Component)]
+pub struct ParticleEmitter {
+    pub effect: String,
+    /// Direction particles fly out in, rotated along with the emitter.
+    pub direction: Vec2,
+    /// Inactive emitters spawn nothing but keep their particles.
+    pub active: bool,
+    /// Despawns the emitter once it is done and its particles are gone.
+    pub despawn_when_done: bool,
+    pub elapsed: f32,
+    pending: f32,
+    burst_done: bool,
+}
+
+impl ParticleEmitter {
+    pub fn new(effect: impl Into<String>) -> Self {
+        Self {
+            effect: effect.into(),
+            direction: Vec2::X,
+            active: true,
+            despawn_when_done: false,
+            elapsed: 0.,
+            pending: 0.,
+            burst_done: false,
+        }
+    }
+
+    /// Plays the effect once and cleans up after itself.
+    pub fn once(effect: impl Into<String>) -> Self {
+        Self {
+            despawn_when_done: true,
+            ..Self::new(effect)
+        }
+    }
+
+    pub fn with_direction(mut self, direction: Vec2) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_active(mut self, active: bool) -> Self {
+        self.active = active;
+        self
+    }
+}
+
+/// Plays `effect` once at `position`.
+pub fn spawn_particle_effect(
+    commands: &mut Commands,
+    effect: impl Into<String>,
+    position: Vec2,
+    direction: Vec2,
+) -> Entity {
+    commands
+        .spawn((
+            Name::new("Particle Effect"),
+            ParticleEmitter::once(effect).with_direction(direction),
+            TransformBundle::from_transform(Transform::from_translation(position.extend(0.))),
+        ))
+        .id()
+}
+
+fn random_between((min, max): (f32, f32)) -> f32 {
+    min + fastrand::f32() * (max - min)
+}
+
+fn spawn_particle(
+    commands: &mut Commands,
+    emitter: Entity,
+    effect: &Arc<ParticleEffect>,
+    sprites: &Sprites,
+    origin: Vec3,
+    direction: Vec2,
+) {
+    let angle = direction.y.atan2(direction.x) + (fastrand::f32() - 0.5) * effect.spread;
+    let offset = Vec2::from_angle(fastrand::f32() * TAU) * effect.radius * fastrand::f32().sqrt();
+    let transform = Transform::from_translation(origin + offset.extend(effect.z))
+        .with_scale(Vec3::splat(effect.scale.sample(0.).unwrap_or(1.)));
+
+    let mut particle = match &effect.sprite {
+        Some(name) => commands.spawn(sprites.bundle(name, transform)),
+        None => commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::splat(effect.size)),
+                ..default()
+            },
+            transform,
+            ..default()
+        }),
+    };
+
+    particle.insert((
+        Name::new("Particle"),
+        Particle {
+            effect: effect.clone(),
+            velocity: Vec2::from_angle(angle) * random_between(effect.speed),
+            age: 0.,
+            lifetime: random_between(effect.lifetime).max(f32::EPSILON),
+        },
+    ));
+
+    if effect.space == ParticleSpace::Local {
+        particle.set_parent(emitter);
+    }
+}
+
+pub fn emit_particles(
+    mut commands: Commands,
+    mut emitters: Query<(
+        Entity,
+        &mut ParticleEmitter,
+        &GlobalTransform,
+        Option<&Children>,
+    )>,
+    live_particles: Query<(), With<Particle>>,
+    particles: Particles,
+    sprites: Sprites,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    for (entity, mut emitter, transform, children) in emitters.iter_mut() {
+        let Some(effect) = particles.effect(&emitter.effect) else {
+            continue;
+        };
+
+        let finished = effect.is_finished(emitter.elapsed, emitter.burst_done);
+        if emitter.active && !finished {
+            let mut count = 0;
+            if !emitter.burst_done {
+                count += effect.burst;
+                emitter.burst_done = true;
+            }
+
+            emitter.pending += effect.rate * dt;
+            let whole = emitter.pending.floor();
+            emitter.pending -= whole;
+            count += whole as u32;
+            emitter.elapsed += dt;
+
+            let (origin, direction) = match effect.space {
+                ParticleSpace::World => {
+                    let (_, rotation, translation) = transform.to_scale_rotation_translation();
+                    (
+                        translation,
+                        (rotation * emitter.direction.extend(0.)).truncate(),
+                    )
+                }
+                ParticleSpace::Local => (Vec3::ZERO, emitter.direction),
+            };
+
+            for _ in 0..count {
+                spawn_particle(&mut commands, entity, &effect, &sprites, origin, direction);
+            }
+        }
+
+        let has_particles = children
+            .is_some_and(|children| children.iter().any(|child| live_particles.contains(*child)));
+        if finished && emitter.despawn_when_done && !has_particles {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,72 @@
+mod effect;
+mod emitter;
+
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+pub use effect::*;
+pub use emitter::*;
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<ParticleEffects>()
+            .init_asset_loader::<ParticleEffectsLoader>()
+            .init_resource::<ParticleLibrary>()
+            .add_systems(Update, (emit_particles, update_particles).chain());
+    }
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct Particle {
+    pub effect: Arc<ParticleEffect>,
+    pub velocity: Vec2,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+type ParticleQuery<'a> = (
+    Entity,
+    &'a mut Particle,
+    &'a mut Transform,
+    Option<&'a mut Sprite>,
+    Option<&'a mut TextureAtlasSprite>,
+);
+
+pub fn update_particles(
+    mut commands: Commands,
+    mut particles: Query<ParticleQuery>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    for (entity, mut particle, mut transform, sprite, atlas_sprite) in particles.iter_mut() {
+        particle.age += dt;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let gravity = particle.effect.gravity;
+        let drag = particle.effect.drag;
+        particle.velocity.y -= gravity * dt;
+        particle.velocity *= (-drag * dt).exp();
+        transform.translation += (particle.velocity * dt).extend(0.);
+
+        let t = particle.age / particle.lifetime;
+        if let Some(scale) = particle.effect.scale.sample(t) {
+            transform.scale = Vec3::new(scale, scale, 1.);
+        }
+
+        if let Some(color) = particle.effect.color.sample(t) {
+            if let Some(mut sprite) = sprite {
+                sprite.color = color.0;
+            }
+            if let Some(mut sprite) = atlas_sprite {
+                sprite.color = color.0;
+            }
+        }
+    }
+}
@@ -1,27 +1,29 @@
-use std::time::Duration;
+use std::{f32::consts::TAU, time::Duration};
 
-use crate::{animator_system, Animator, AnimatorStateMachine};
-
-use super::{CameraOptions, PlayerAttackEvent, PlayerMarker, PlayerMotor};
-use bevy::{
-    math::{cubic_splines::CubicCurve, vec2},
-    prelude::*,
-    window::PrimaryWindow,
+use crate::{
+    core::GameplaySet, AnimationMarker, Animator, AnimatorPlugin, AnimatorStateMachine, ClipPose,
+    KeyframeClip,
 };
-use lazy_static::lazy_static;
+
+use super::{CameraOptions, CursorWorldPosition, PlayerAttackEvent, PlayerMarker, PlayerMotor};
+use bevy::prelude::*;
 
 pub struct PlayerAnimatorPlugin;
 
 impl Plugin for PlayerAnimatorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_plugins((
+            AnimatorPlugin::<WeaponAnimationState>::default(),
+            AnimatorPlugin::<PlayerSpriteAnimationState>::default(),
+        ))
+        .add_systems(
             Update,
             (
                 animate_player_sprite,
                 animate_player_attack,
                 animate_player_weapon,
-                animator_system::<WeaponAnimationState>,
-            ),
+            )
+                .in_set(GameplaySet),
         );
     }
 }
@@ -33,15 +35,11 @@ pub struct PlayerWeaponMarker;
 pub struct PlayerSpriteMarker;
 
 pub const ATTACK_ANIMATION_DURATION: f32 = 0.23;
-const ATTACK_CURVE_CONTROL_POINTS: [[Vec2; 4]; 1] =
-    [[vec2(1., 0.), vec2(0.25, 1.105), vec2(0., 1.), vec2(0., 0.)]];
+/// Marker of the attack swing at which the weapon connects.
+pub const WEAPON_HIT_MARKER: &str = "hit";
+const ATTACK_MARKERS: [AnimationMarker; 1] = [AnimationMarker::new(0.4, WEAPON_HIT_MARKER)];
 
-lazy_static! {
-    pub static ref ATTACK_CURVE: CubicCurve<Vec2> =
-        Bezier::new(ATTACK_CURVE_CONTROL_POINTS).to_curve();
-}
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Reflect)]
 pub struct WeaponAnimationState {
     pub look_direction: Vec2,
     pub weapon_pivot: Vec2,
@@ -58,7 +56,7 @@ impl Default for WeaponAnimationState {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Reflect)]
 pub enum WeaponAnimationStateState {
     #[default]
     Idle,
@@ -68,9 +66,7 @@ pub enum WeaponAnimationStateState {
 }
 
 impl AnimatorStateMachine for WeaponAnimationState {
-    fn calculate_transform(&self, t: f32) -> Transform {
-        use WeaponAnimationStateState::*;
-
+    fn calculate_transform(&self, _t: f32) -> Transform {
         let mut offset = Transform::IDENTITY;
         offset.rotate_z(self.look_direction.y.atan2(self.look_direction.x));
         offset.scale.y = self.look_direction.x.signum();
@@ -79,12 +75,26 @@ impl AnimatorStateMachine for WeaponAnimationState {
         .extend(0.);
 
         offset
-            * match self.state {
-                Idle => Transform::IDENTITY,
-                Attacking { range } => {
-                    Transform::from_xyz(ATTACK_CURVE.position(t).y * range, 0., 0.)
-                }
+    }
+
+    fn clip(&self, asset_server: &AssetServer) -> Option<Handle<KeyframeClip>> {
+        match self.state {
+            WeaponAnimationStateState::Idle => None,
+            WeaponAnimationStateState::Attacking { .. } => {
+                Some(asset_server.load("animations/sword_swing.anim.ron"))
             }
+        }
+    }
+
+    /// The swing clip is authored in fractions of the attack range.
+    fn apply_clip(&self, transform: Transform, pose: &ClipPose) -> Transform {
+        let range = match self.state {
+            WeaponAnimationStateState::Idle => 0.,
+            WeaponAnimationStateState::Attacking { range } => range,
+        };
+
+        transform
+            * Transform::from_translation((pose.translation.unwrap_or_default() * range).extend(0.))
     }
 
     fn duration(&self) -> std::time::Duration {
@@ -95,54 +105,93 @@ impl AnimatorStateMachine for WeaponAnimationState {
             }
         }
     }
+
+    fn markers(&self) -> &'static [AnimationMarker] {
+        match self.state {
+            WeaponAnimationStateState::Idle => &[],
+            WeaponAnimationStateState::Attacking { .. } => &ATTACK_MARKERS,
+        }
+    }
+}
+
+pub const BOB_FREQUENCY: f32 = 27.5;
+
+/// Walking bob of the hero sprite, looping once per bob period. Meant to be
+/// layered additively so other animations of the sprite can play on top.
+#[derive(Debug, Default, Clone, Reflect)]
+pub struct PlayerSpriteAnimationState {
+    pub bob_intensity: f32,
+}
+
+impl AnimatorStateMachine for PlayerSpriteAnimationState {
+    fn calculate_transform(&self, t: f32) -> Transform {
+        Transform::from_xyz(0., (t * TAU).sin() * self.bob_intensity, 0.)
+    }
+
+    fn duration(&self) -> Duration {
+        Duration::from_secs_f32(TAU / BOB_FREQUENCY)
+    }
+
+    fn next(&self) -> Option<Self> {
+        Some(self.clone())
+    }
 }
 
 pub fn animate_player_sprite(
-    player: Query<(&PlayerMotor,), Without<PlayerSpriteMarker>>,
-    mut sprite: Query<&mut Transform, With<PlayerSpriteMarker>>,
-    time: Res<Time>,
+    players: Query<&PlayerMotor, Without<PlayerSpriteMarker>>,
+    mut sprites: Query<
+        (&Parent, &mut Animator<PlayerSpriteAnimationState>),
+        With<PlayerSpriteMarker>,
+    >,
     director: Res<CameraOptions>,
 ) {
-    let (motor,) = player.single();
-    let mut sprite_transform = sprite.single_mut();
+    for (parent, mut animator) in sprites.iter_mut() {
+        let Ok(motor) = players.get(parent.get()) else {
+            continue;
+        };
 
-    let bob_intensity =
-        motor.velocity.length() / motor.max_speed + motor.velocity.y.abs() / motor.max_speed;
+        let bob_intensity =
+            motor.velocity.length() / motor.max_speed + motor.velocity.y.abs() / motor.max_speed;
 
-    sprite_transform.translation.y =
-        (time.elapsed_seconds() * 27.5).sin() * bob_intensity * director.character_bob_intensity;
+        animator.mutate_state(|state| {
+            state.bob_intensity = bob_intensity * director.character_bob_intensity
+        });
+    }
 }
 
 pub fn animate_player_weapon(
-    mut weapon_animator: Query<&mut Animator<WeaponAnimationState>>,
-    weapon_pivot: Query<&GlobalTransform, With<PlayerMarker>>,
-    camera: Query<(&GlobalTransform, &Camera), Without<PlayerMarker>>,
-    window: Query<&Window, With<PrimaryWindow>>,
+    mut weapon_animators: Query<(&Parent, &mut Animator<WeaponAnimationState>)>,
+    weapon_pivots: Query<&GlobalTransform, With<PlayerMarker>>,
+    cursor: Res<CursorWorldPosition>,
 ) {
-    let mut animator = weapon_animator.single_mut();
-    let (camera_global, camera) = camera.single();
-    let weapon_pivot = weapon_pivot.single();
-    let window = window.single();
-
-    if let Some(cursor_pos) = window
-        .cursor_position()
-        .and_then(|cursor| camera.viewport_to_world_2d(camera_global, cursor))
-    {
+    let Some(cursor_pos) = cursor.0 else {
+        return;
+    };
+
+    for (parent, mut animator) in weapon_animators.iter_mut() {
+        let Ok(weapon_pivot) = weapon_pivots.get(parent.get()) else {
+            continue;
+        };
+
         let direction = cursor_pos - weapon_pivot.translation().truncate();
         animator.mutate_state(|state| state.look_direction = direction.clamp_length(0., 1.));
     }
 }
 
 pub fn animate_player_attack(
-    mut weapon: Query<&mut Animator<WeaponAnimationState>>,
+    mut weapons: Query<(&Parent, &mut Animator<WeaponAnimationState>)>,
     mut events: EventReader<PlayerAttackEvent>,
 ) {
-    let mut weapon = weapon.single_mut();
+    for event in events.iter() {
+        for (parent, mut weapon) in weapons.iter_mut() {
+            if parent.get() != event.player_entity {
+                continue;
+            }
 
-    if events.iter().next().is_some() {
-        weapon.transition_into(WeaponAnimationState {
-            state: WeaponAnimationStateState::Attacking { range: 5. },
-            ..WeaponAnimationState::default()
-        });
+            weapon.transition_into(WeaponAnimationState {
+                state: WeaponAnimationStateState::Attacking { range: 5. },
+                ..WeaponAnimationState::default()
+            });
+        }
     }
 }
@@ -1,7 +1,13 @@
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
 use bevy_inspector_egui::{prelude::ReflectInspectorOptions, InspectorOptions};
 
-use crate::player::PlayerMarker;
+use super::{
+    advance_camera_scripts, apply_camera_shake, frame_targets, kick_on_attack, shake_on_damage,
+    skip_camera_scripts, snap_camera_to_pixels, spawn_upscale_camera, sync_render_target,
+    CameraScript, CameraScriptEvent, CameraShake, CameraShakeEvent, CameraTarget,
+    PixelPerfectTarget, TargetFraming, UpscaleSprite,
+};
+use crate::{player::PlayerMarker, sound::AudioListener};
 
 pub struct CameraPlugin;
 
@@ -10,7 +16,28 @@ impl Plugin for CameraPlugin {
         app.register_type::<CameraOptions>()
             .init_resource::<CameraOptions>()
             .register_type::<CameraMotor>()
-            .add_systems(Update, (move_camera,));
+            .register_type::<CameraShake>()
+            .register_type::<CameraTarget>()
+            .register_type::<CameraBounds>()
+            .init_resource::<CameraBounds>()
+            .init_resource::<CursorWorldPosition>()
+            .init_resource::<PixelPerfectTarget>()
+            .add_event::<CameraShakeEvent>()
+            .add_event::<CameraScriptEvent>()
+            .add_systems(Startup, spawn_upscale_camera)
+            .add_systems(PreUpdate, update_cursor_world_position)
+            .add_systems(
+                Update,
+                (
+                    skip_camera_scripts.before(advance_camera_scripts),
+                    advance_camera_scripts.before(move_camera),
+                    move_camera,
+                    (shake_on_damage, kick_on_attack).before(apply_camera_shake),
+                    apply_camera_shake.after(move_camera),
+                    snap_camera_to_pixels.after(apply_camera_shake),
+                    sync_render_target.after(move_camera),
+                ),
+            );
     }
 }
 
@@ -20,6 +47,26 @@ pub struct CameraOptions {
     pub follow_speed: f32,
     pub character_to_cursor_center: f32,
     pub character_bob_intensity: f32,
+    /// Accessibility toggle, disables screen shake and camera kicks entirely.
+    pub screen_shake: bool,
+    pub shake_intensity: f32,
+    pub hero_damage_trauma: f32,
+    pub enemy_damage_trauma: f32,
+    pub killing_blow_trauma: f32,
+    /// Pixels the camera is pushed along the player's attacks.
+    pub attack_kick: f32,
+    /// Screen pixels per world pixel. Rounded to a whole number when
+    /// `pixel_perfect` is on.
+    pub zoom: f32,
+    /// How quickly the camera eases towards a new `zoom`.
+    pub zoom_speed: f32,
+    /// Furthest the camera zooms out to fit all of its targets.
+    pub min_zoom: f32,
+    /// Pixels kept between the framed targets and the edge of the view.
+    pub framing_padding: f32,
+    /// Renders the world at low resolution and upscales it by an integer
+    /// factor, keeping every pixel the same size.
+    pub pixel_perfect: bool,
 }
 
 impl Default for CameraOptions {
@@ -28,6 +75,17 @@ impl Default for CameraOptions {
             follow_speed: 30.,
             character_to_cursor_center: 0.25,
             character_bob_intensity: 0.75,
+            screen_shake: true,
+            shake_intensity: 1.,
+            hero_damage_trauma: 0.5,
+            enemy_damage_trauma: 0.15,
+            killing_blow_trauma: 0.3,
+            attack_kick: 1.5,
+            zoom: 5.,
+            zoom_speed: 8.,
+            min_zoom: 2.,
+            framing_padding: 24.,
+            pixel_perfect: true,
         }
     }
 }
@@ -35,19 +93,44 @@ impl Default for CameraOptions {
 #[derive(Component, Reflect)]
 pub struct CameraMotor {
     pub desired_location: Vec3,
+    /// Smoothed follow position, effects such as shake are applied on top.
+    pub location: Vec3,
+    /// Smoothed towards [`CameraOptions::zoom`], or less to fit all targets.
+    pub zoom: f32,
+    /// Zoom actually shown, after any [`CameraScript`].
+    pub view_zoom: f32,
 }
 
 impl Default for CameraMotor {
     fn default() -> Self {
         Self {
             desired_location: Vec3::ZERO,
+            location: Vec3::ZERO,
+            zoom: 5.,
+            view_zoom: 5.,
         }
     }
 }
 
+/// World-space area the camera view is kept inside, usually the level.
+/// A level smaller than the view is centered instead.
+#[derive(Debug, Default, Clone, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct CameraBounds {
+    pub rect: Option<Rect>,
+}
+
+/// Where the mouse cursor points in the world, `None` when it is outside
+/// the window. Accounts for zoom and the pixel perfect render target.
+#[derive(Debug, Default, Clone, Copy, Resource)]
+pub struct CursorWorldPosition(pub Option<Vec2>);
+
 #[derive(Bundle)]
 pub struct CameraBundle {
     pub director: CameraMotor,
+    pub shake: CameraShake,
+    pub ui: UiCameraConfig,
+    pub listener: AudioListener,
     pub camera: Camera2dBundle,
 }
 
@@ -55,6 +138,9 @@ impl Default for CameraBundle {
     fn default() -> Self {
         Self {
             director: CameraMotor::default(),
+            shake: CameraShake::default(),
+            ui: UiCameraConfig::default(),
+            listener: AudioListener,
 
             camera: Camera2dBundle {
                 projection: OrthographicProjection {
@@ -69,32 +155,146 @@ impl Default for CameraBundle {
     }
 }
 
+pub fn update_cursor_world_position(
+    mut cursor_world: ResMut<CursorWorldPosition>,
+    camera: Query<(&GlobalTransform, &Camera), With<CameraMotor>>,
+    upscale_sprite: Query<&Transform, With<UpscaleSprite>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    target: Res<PixelPerfectTarget>,
+    options: Res<CameraOptions>,
+) {
+    let (Ok(window), Ok((camera_global, camera))) = (window.get_single(), camera.get_single())
+    else {
+        cursor_world.0 = None;
+        return;
+    };
+
+    cursor_world.0 = window.cursor_position().and_then(|cursor| {
+        if options.pixel_perfect {
+            // The world camera renders off screen, so map through the upscaled image.
+            let from_center = Vec2::new(
+                cursor.x - window.width() / 2.,
+                window.height() / 2. - cursor.y,
+            );
+            let image_offset = upscale_sprite
+                .get_single()
+                .map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+            let scale = target.scale.max(1) as f32;
+            Some(camera_global.translation().truncate() + (from_center - image_offset) / scale)
+        } else {
+            camera.viewport_to_world_2d(camera_global, cursor)
+        }
+    });
+}
+
+/// The heroes and [`CameraTarget`]s the camera keeps in view.
+#[derive(SystemParam)]
+pub struct CameraSubjects<'w, 's> {
+    targets: Query<'w, 's, (&'static GlobalTransform, &'static CameraTarget)>,
+    heroes: Query<'w, 's, &'static GlobalTransform, With<PlayerMarker>>,
+}
+
+impl CameraSubjects<'_, '_> {
+    pub fn framing(&self) -> Option<TargetFraming> {
+        let heroes: Vec<_> = self
+            .heroes
+            .iter()
+            .map(|hero| hero.translation().truncate())
+            .collect();
+
+        frame_targets(
+            self.targets
+                .iter()
+                .map(|(transform, target)| (transform.translation().truncate(), *target)),
+            &heroes,
+        )
+    }
+}
+
 pub fn move_camera(
-    mut camera: Query<
-        (&mut Transform, &GlobalTransform, &Camera, &mut CameraMotor),
-        Without<PlayerMarker>,
-    >,
+    mut camera: Query<(&mut Transform, &mut CameraMotor, Option<&CameraScript>)>,
+    subjects: CameraSubjects,
     window: Query<&Window, With<PrimaryWindow>>,
-    character: Query<&Transform, With<PlayerMarker>>,
+    cursor: Res<CursorWorldPosition>,
+    bounds: Res<CameraBounds>,
     time: Res<Time>,
     director: Res<CameraOptions>,
 ) {
-    let window = window.single();
-
-    let (mut camera_transform, camera_global, camera, mut motor): (_, _, &Camera, _) =
-        camera.single_mut();
-    let character_transform = character.single();
-
-    if let Some(Vec2 { x, y }) = window
-        .cursor_position()
-        .and_then(|cursor| camera.viewport_to_world_2d(camera_global, cursor))
-    {
-        motor.desired_location = character_transform.translation
-            * (1. - director.character_to_cursor_center)
-            + Vec3::new(x, y, 0.) * (director.character_to_cursor_center);
+    let Ok((mut camera_transform, mut motor, script)) = camera.get_single_mut() else {
+        return;
+    };
+    let view_size = window.get_single().map_or(Vec2::ZERO, |window| {
+        Vec2::new(window.width(), window.height())
+    });
+
+    let mut fit_zoom = f32::INFINITY;
+    if let Some(framing) = subjects.framing() {
+        let center = framing.center;
+        motor.desired_location = cursor
+            .0
+            .map_or(center, |cursor| {
+                center.lerp(cursor, director.character_to_cursor_center)
+            })
+            .extend(0.);
+
+        let framed_size = framing.size + director.framing_padding * 2.;
+        if view_size.min_element() > 0. {
+            fit_zoom = (view_size / framed_size)
+                .min_element()
+                .max(director.min_zoom);
+        }
     }
 
     let dt = time.delta_seconds();
-    camera_transform.translation = camera_transform.translation * (1. - dt * director.follow_speed)
+    // Integer scales cannot blend, so pixel perfect zoom changes snap.
+    motor.zoom = if director.pixel_perfect {
+        director.zoom.round().min(fit_zoom.floor()).max(1.)
+    } else {
+        let target_zoom = director.zoom.min(fit_zoom).max(0.01);
+        motor.zoom + (target_zoom - motor.zoom) * (dt * director.zoom_speed).min(1.)
+    };
+
+    motor.location = motor.location * (1. - dt * director.follow_speed)
         + motor.desired_location * dt * director.follow_speed;
+    motor.location = clamp_to_bounds(motor.location, bounds.rect, view_size / motor.zoom);
+
+    let mut view_location = motor.location;
+    let mut view_zoom = motor.zoom;
+    if let Some(script) = script {
+        let (location, zoom) = script.sample();
+        let weight = script.weight();
+        view_location = view_location.lerp(location.extend(0.), weight);
+        if let Some(zoom) = zoom {
+            view_zoom += (zoom - view_zoom) * weight;
+        }
+    }
+    if director.pixel_perfect {
+        view_zoom = view_zoom.round().max(1.);
+    }
+
+    motor.view_zoom = view_zoom;
+    camera_transform.translation =
+        clamp_to_bounds(view_location, bounds.rect, view_size / view_zoom);
+}
+
+/// Keeps a view of `view_size` around `location` inside `bounds`, centering
+/// it along axes where the bounds are smaller than the view.
+fn clamp_to_bounds(location: Vec3, bounds: Option<Rect>, view_size: Vec2) -> Vec3 {
+    let Some(rect) = bounds else {
+        return location;
+    };
+
+    let clamp_axis = |location: f32, min: f32, max: f32, half_view: f32| {
+        if max - min <= half_view * 2. {
+            (min + max) / 2.
+        } else {
+            location.clamp(min + half_view, max - half_view)
+        }
+    };
+
+    Vec3::new(
+        clamp_axis(location.x, rect.min.x, rect.max.x, view_size.x / 2.),
+        clamp_axis(location.y, rect.min.y, rect.max.y, view_size.y / 2.),
+        location.z,
+    )
 }
@@ -0,0 +1,222 @@
+use bevy::prelude::*;
+
+use super::CameraMotor;
+use crate::{
+    settings::{Action, Controls},
+    Ease,
+};
+
+/// One step of a [`CameraScript`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CameraShot {
+    /// Holds on a point, e.g. a door that just opened.
+    Focus {
+        point: Vec2,
+        zoom: Option<f32>,
+        duration: f32,
+    },
+    /// Travels along a polyline at an even speed, shaped by `ease`.
+    Rail {
+        points: Vec<Vec2>,
+        zoom: Option<f32>,
+        duration: f32,
+        ease: Ease,
+    },
+}
+
+impl CameraShot {
+    pub fn focus(point: Vec2, duration: f32) -> Self {
+        Self::Focus {
+            point,
+            zoom: None,
+            duration,
+        }
+    }
+
+    pub fn rail(points: impl IntoIterator<Item = Vec2>, duration: f32, ease: Ease) -> Self {
+        Self::Rail {
+            points: points.into_iter().collect(),
+            zoom: None,
+            duration,
+            ease,
+        }
+    }
+
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        match &mut self {
+            Self::Focus { zoom: z, .. } | Self::Rail { zoom: z, .. } => *z = Some(zoom),
+        }
+        self
+    }
+
+    pub fn duration(&self) -> f32 {
+        match self {
+            Self::Focus { duration, .. } | Self::Rail { duration, .. } => *duration,
+        }
+    }
+
+    /// Where the shot looks and how far it zooms, `time` seconds in.
+    pub fn sample(&self, time: f32) -> (Vec2, Option<f32>) {
+        match self {
+            Self::Focus { point, zoom, .. } => (*point, *zoom),
+            Self::Rail {
+                points,
+                zoom,
+                duration,
+                ease,
+            } => {
+                let t = if *duration > 0. {
+                    ease.apply(time / duration)
+                } else {
+                    1.
+                };
+                (point_along(points, t), *zoom)
+            }
+        }
+    }
+}
+
+/// Point at `t` of the way along the polyline, measured by length.
+fn point_along(points: &[Vec2], t: f32) -> Vec2 {
+    let length: f32 = points
+        .windows(2)
+        .map(|pair| pair[0].distance(pair[1]))
+        .sum();
+    let mut remaining = length * t.clamp(0., 1.);
+
+    for pair in points.windows(2) {
+        let segment = pair[0].distance(pair[1]);
+        if remaining <= segment && segment > 0. {
+            return pair[0].lerp(pair[1], remaining / segment);
+        }
+        remaining -= segment;
+    }
+
+    points.last().copied().unwrap_or_default()
+}
+
+/// Takes control of the camera for a sequence of shots, for cutscenes and
+/// room transitions. Control blends in from and back out to the regular
+/// follow camera over `blend` seconds.
+#[derive(Debug, Clone, Component)]
+pub struct CameraScript {
+    pub shots: Vec<CameraShot>,
+    pub blend: f32,
+    pub ease: Ease,
+    pub elapsed: f32,
+    released_at: Option<f32>,
+}
+
+impl CameraScript {
+    pub fn new(shots: impl IntoIterator<Item = CameraShot>) -> Self {
+        Self {
+            shots: shots.into_iter().collect(),
+            blend: 0.5,
+            ease: Ease::SineInOut,
+            elapsed: 0.,
+            released_at: None,
+        }
+    }
+
+    pub fn with_blend(mut self, blend: f32, ease: Ease) -> Self {
+        self.blend = blend;
+        self.ease = ease;
+        self
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.shots.iter().map(CameraShot::duration).sum()
+    }
+
+    /// Hands control back early, blending out from the current shot.
+    pub fn release(&mut self) {
+        self.released_at = Some(self.released_at.unwrap_or(self.elapsed));
+    }
+
+    fn release_time(&self) -> f32 {
+        self.released_at.unwrap_or_else(|| self.duration())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.release_time() + self.blend
+    }
+
+    /// How much of the camera the script controls, from 0 to 1.
+    pub fn weight(&self) -> f32 {
+        if self.blend <= 0. {
+            return if self.is_finished() { 0. } else { 1. };
+        }
+
+        let blend_in = self.elapsed / self.blend;
+        let blend_out = 1. - (self.elapsed - self.release_time()) / self.blend;
+        self.ease.apply(blend_in.min(blend_out))
+    }
+
+    /// The current shot's look point and zoom, holding the last pose once
+    /// the script has been released.
+    pub fn sample(&self) -> (Vec2, Option<f32>) {
+        let mut time = self.elapsed.min(self.release_time());
+
+        for (i, shot) in self.shots.iter().enumerate() {
+            if time < shot.duration() || i == self.shots.len() - 1 {
+                return shot.sample(time);
+            }
+            time -= shot.duration();
+        }
+
+        (Vec2::ZERO, None)
+    }
+}
+
+#[derive(Debug, Clone, Event)]
+pub enum CameraScriptEvent {
+    Play(CameraScript),
+    /// Releases the playing script, see [`CameraScript::release`].
+    Release,
+}
+
+pub fn advance_camera_scripts(
+    mut commands: Commands,
+    mut cameras: Query<(Entity, Option<&mut CameraScript>), With<CameraMotor>>,
+    mut events: EventReader<CameraScriptEvent>,
+    time: Res<Time>,
+) {
+    let events: Vec<_> = events.iter().cloned().collect();
+
+    for (entity, script) in cameras.iter_mut() {
+        let mut script = script.map(|script| script.into_inner());
+        let mut replaced = false;
+
+        for event in events.iter() {
+            match event {
+                CameraScriptEvent::Play(new_script) => {
+                    commands.entity(entity).insert(new_script.clone());
+                    replaced = true;
+                }
+                CameraScriptEvent::Release => {
+                    if let Some(script) = script.as_mut() {
+                        script.release();
+                    }
+                }
+            }
+        }
+
+        if let Some(script) = script.filter(|_| !replaced) {
+            script.elapsed += time.delta_seconds();
+            if script.is_finished() {
+                commands.entity(entity).remove::<CameraScript>();
+            }
+        }
+    }
+}
+
+/// Lets the hero skip a playing script by attacking.
+pub fn skip_camera_scripts(
+    controls: Controls,
+    scripts: Query<(), With<CameraScript>>,
+    mut events: EventWriter<CameraScriptEvent>,
+) {
+    if !scripts.is_empty() && controls.just_pressed(Action::Attack) {
+        events.send(CameraScriptEvent::Release);
+    }
+}
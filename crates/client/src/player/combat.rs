@@ -1,109 +1,148 @@
-use bevy::{math::vec2, prelude::*, reflect::Reflect, window::PrimaryWindow};
+use bevy::{prelude::*, reflect::Reflect};
 use bevy_rapier2d::prelude::*;
 
-use crate::{core::DealDamageEvent, player::PlayerMarker};
+use crate::{
+    core::{DamageKind, DealDamageEvent, GameplaySet},
+    player::{
+        CameraScript, CursorWorldPosition, PlayerMarker, WeaponAnimationState, WEAPON_HIT_MARKER,
+    },
+    settings::{Action, Controls},
+    AnimationEvent,
+};
 
 pub struct CombatPlugin;
 
 impl Plugin for CombatPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<PlayerAttackEvent>()
+        app.register_type::<AttackStats>()
+            .add_event::<PlayerAttackEvent>()
             .add_event::<EntityHitEvent>()
             .add_systems(
                 Update,
-                (attack_input_system, attack_provider, convert_hits_to_damage),
+                (attack_input_system, attack_provider, convert_hits_to_damage).in_set(GameplaySet),
             );
     }
 }
 
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct AttackStats {
+    pub damage: u32,
+    pub range: f32,
+}
+
+impl Default for AttackStats {
+    fn default() -> Self {
+        Self {
+            damage: 1,
+            range: 8.,
+        }
+    }
+}
+
 #[derive(Debug, Event, Reflect)]
 pub struct PlayerAttackEvent {
-    player_entity: Entity,
-    player_pos: Vec2,
-    direction: Vec2,
+    pub player_entity: Entity,
+    pub player_pos: Vec2,
+    pub direction: Vec2,
 }
 
 #[derive(Debug, Event, Reflect)]
 pub struct EntityHitEvent {
     from_pos: Vec2,
     entity: Entity,
+    attacker: Entity,
 }
 
+/// Attacks are held back while a [`CameraScript`] has the camera, so the
+/// press that skips it does not swing as well.
 pub fn attack_input_system(
     player: Query<(Entity, &GlobalTransform), With<PlayerMarker>>,
-    inputs: Res<Input<MouseButton>>,
+    scripts: Query<(), With<CameraScript>>,
+    controls: Controls,
     mut event_queue: EventWriter<PlayerAttackEvent>,
-    window: Query<&Window, With<PrimaryWindow>>,
-    camera: Query<(&GlobalTransform, &Camera), Without<PlayerMarker>>,
+    cursor: Res<CursorWorldPosition>,
 ) {
-    let (player_entity, player_transform) = player.single();
-    let (global_transform, camera) = camera.single();
-    let window = window.single();
+    let Ok((player_entity, player_transform)) = player.get_single() else {
+        return;
+    };
+    if !scripts.is_empty() {
+        return;
+    }
 
-    if inputs.just_pressed(MouseButton::Left) {
-        if let Some(cursor_pos) = window
-            .cursor_position()
-            .and_then(|cursor| camera.viewport_to_world_2d(global_transform, cursor))
-        {
+    if controls.just_pressed(Action::Attack) {
+        if let Some(cursor_pos) = cursor.0 {
+            let player_pos = player_transform.translation().truncate();
             event_queue.send(PlayerAttackEvent {
                 player_entity,
-                player_pos: vec2(
-                    global_transform.translation().x,
-                    global_transform.translation().y,
-                ),
-                direction: (cursor_pos
-                    - vec2(
-                        player_transform.translation().x,
-                        player_transform.translation().y,
-                    ))
-                .normalize_or_zero(),
+                player_pos,
+                direction: (cursor_pos - player_pos).normalize_or_zero(),
             })
         }
     }
 }
 
+/// Casts the attack once the weapon swing reaches its hit marker, so hits land
+/// in sync with the animation rather than on the click.
 pub fn attack_provider(
     rapier_ctx: Res<RapierContext>,
-    mut attack_events: EventReader<PlayerAttackEvent>,
+    attackers: Query<(&AttackStats, &GlobalTransform)>,
+    weapons: Query<&Parent>,
+    mut swing_events: EventReader<AnimationEvent<WeaponAnimationState>>,
     mut hit_events: EventWriter<EntityHitEvent>,
 ) {
-    for PlayerAttackEvent {
-        player_pos,
-        direction,
-        player_entity,
-    } in attack_events.into_iter()
+    for AnimationEvent { entity, state, .. } in swing_events
+        .iter()
+        .filter(|event| event.marker == WEAPON_HIT_MARKER)
     {
+        let Ok(player_entity) = weapons.get(*entity).map(|parent| parent.get()) else {
+            continue;
+        };
+        let Ok((stats, player_transform)) = attackers.get(player_entity) else {
+            continue;
+        };
+        let player_pos = player_transform.translation().truncate();
+
         if let Some((entity, _hit)) = rapier_ctx.cast_shape(
-            *player_pos,
+            player_pos,
             0.,
-            *direction,
+            state.look_direction.normalize_or_zero(),
             &Collider::ball(1.),
-            8.,
+            stats.range,
             QueryFilter::new()
-                .exclude_collider(*player_entity)
+                .exclude_collider(player_entity)
                 .exclude_sensors(),
         ) {
             hit_events.send(EntityHitEvent {
                 entity,
-                from_pos: *player_pos,
+                from_pos: player_pos,
+                attacker: player_entity,
             })
         }
     }
 }
 
 pub fn convert_hits_to_damage(
+    attackers: Query<&AttackStats>,
     mut hit_events: EventReader<EntityHitEvent>,
     mut damage_events: EventWriter<DealDamageEvent>,
 ) {
     for EntityHitEvent {
         entity,
         from_pos: from_position,
+        attacker,
     } in hit_events.into_iter()
     {
+        let damage = attackers
+            .get(*attacker)
+            .map(|stats| stats.damage)
+            .unwrap_or(AttackStats::default().damage);
+
         damage_events.send(DealDamageEvent {
-            damage: 1,
+            damage,
+            kind: DamageKind::Physical,
             target: *entity,
             from_position: *from_position,
+            dealt_by: Some(*attacker),
         })
     }
 }
@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+
+/// Something the camera keeps in view. The camera centers on the weighted
+/// average of all targets and zooms out to fit them.
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct CameraTarget {
+    pub weight: f32,
+    /// Space kept around the target, in pixels.
+    pub radius: f32,
+    /// Only counts while this close to a hero, fading out over the outer
+    /// half of the range. `None` always counts.
+    pub range: Option<f32>,
+}
+
+impl Default for CameraTarget {
+    fn default() -> Self {
+        Self {
+            weight: 1.,
+            radius: 8.,
+            range: None,
+        }
+    }
+}
+
+impl CameraTarget {
+    pub fn new(weight: f32, radius: f32) -> Self {
+        Self {
+            weight,
+            radius,
+            range: None,
+        }
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// How much the target counts at `position`, from 0 to 1.
+    fn presence(&self, position: Vec2, heroes: &[Vec2]) -> f32 {
+        let Some(range) = self.range else {
+            return 1.;
+        };
+
+        let distance = heroes
+            .iter()
+            .map(|hero| hero.distance(position))
+            .fold(f32::INFINITY, f32::min);
+        (2. - 2. * distance / range.max(f32::EPSILON)).clamp(0., 1.)
+    }
+}
+
+/// View the camera should show to keep its targets in view, centered on
+/// their weighted center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TargetFraming {
+    pub center: Vec2,
+    pub size: Vec2,
+}
+
+/// Frames the targets at their positions, `None` when none of them counts.
+pub fn frame_targets(
+    targets: impl IntoIterator<Item = (Vec2, CameraTarget)>,
+    heroes: &[Vec2],
+) -> Option<TargetFraming> {
+    let targets: Vec<_> = targets
+        .into_iter()
+        .map(|(position, target)| (position, target, target.presence(position, heroes)))
+        .filter(|(_, target, presence)| target.weight * *presence > 0.)
+        .collect();
+
+    let total_weight: f32 = targets
+        .iter()
+        .map(|(_, target, presence)| target.weight * *presence)
+        .sum();
+    if total_weight <= 0. {
+        return None;
+    }
+
+    let center = targets
+        .iter()
+        .map(|(position, target, presence)| *position * target.weight * *presence)
+        .sum::<Vec2>()
+        / total_weight;
+
+    // Fading targets are pulled towards the center so the zoom eases out.
+    let (min, max) = targets.iter().fold(
+        (center, center),
+        |(min, max), (position, target, presence)| {
+            let position = center + (*position - center) * *presence;
+            let radius = Vec2::splat(target.radius * *presence);
+            (min.min(position - radius), max.max(position + radius))
+        },
+    );
+
+    // The camera centers on the weighted center, so the view has to reach
+    // the farther side in both directions.
+    Some(TargetFraming {
+        center,
+        size: (max - center).max(center - min) * 2.,
+    })
+}
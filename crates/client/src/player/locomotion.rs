@@ -1,12 +1,17 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+use crate::{
+    core::GameplaySet,
+    settings::{Action, Controls},
+};
+
 pub struct PlayerLocomotionPlugin;
 
 impl Plugin for PlayerLocomotionPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<PlayerMotor>()
-            .add_systems(Update, handle_player_movement);
+            .add_systems(Update, handle_player_movement.in_set(GameplaySet));
     }
 }
 
@@ -37,17 +42,15 @@ impl Default for PlayerMotor {
 
 pub fn handle_player_movement(
     mut character: Query<(&mut PlayerMotor, &mut Velocity), With<PlayerMarker>>,
-    keys: Res<Input<KeyCode>>,
+    controls: Controls,
     time: Res<Time>,
 ) {
     let (mut motor, mut vel) = character.single_mut();
     let deceleration = (1. + motor.drag * time.delta_seconds()).clamp(0., f32::INFINITY);
     motor.velocity /= deceleration;
 
-    motor.wish_direction.y = keys.pressed(KeyCode::W).then_some(1.).unwrap_or_default()
-        + keys.pressed(KeyCode::S).then_some(-1.).unwrap_or_default();
-    motor.wish_direction.x = keys.pressed(KeyCode::D).then_some(1.).unwrap_or_default()
-        + keys.pressed(KeyCode::A).then_some(-1.).unwrap_or_default();
+    motor.wish_direction.y = controls.axis(Action::MoveDown, Action::MoveUp);
+    motor.wish_direction.x = controls.axis(Action::MoveLeft, Action::MoveRight);
     motor.wish_direction = motor.wish_direction.normalize_or_zero() * 1.;
     let wish_vector = motor.wish_direction;
 
@@ -1,9 +1,17 @@
 mod animation;
 mod camera;
+mod camera_script;
 mod combat;
+mod framing;
 mod locomotion;
+mod pixel_perfect;
+mod shake;
 
 pub use animation::*;
 pub use camera::*;
+pub use camera_script::*;
 pub use combat::*;
+pub use framing::*;
 pub use locomotion::*;
+pub use pixel_perfect::*;
+pub use shake::*;
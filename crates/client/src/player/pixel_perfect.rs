@@ -0,0 +1,201 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
+    },
+    window::{PrimaryWindow, WindowRef},
+};
+
+use super::{CameraMotor, CameraOptions};
+
+/// Layer only the upscaling camera renders, so it never sees the world.
+pub const UPSCALE_LAYER: u8 = 1;
+/// Extra texels around the low resolution target, covering the sub-pixel
+/// offset of the upscaled image.
+const TARGET_MARGIN: u32 = 2;
+
+/// Low resolution image the world is rendered to in pixel perfect mode,
+/// shown on screen at an integer `scale`.
+#[derive(Resource)]
+pub struct PixelPerfectTarget {
+    pub image: Handle<Image>,
+    pub scale: u32,
+}
+
+impl FromWorld for PixelPerfectTarget {
+    fn from_world(world: &mut World) -> Self {
+        let size = Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        let mut image = Image {
+            texture_descriptor: TextureDescriptor {
+                label: Some("pixel_perfect_target"),
+                size,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Bgra8UnormSrgb,
+                mip_level_count: 1,
+                sample_count: 1,
+                usage: TextureUsages::TEXTURE_BINDING
+                    | TextureUsages::COPY_DST
+                    | TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            },
+            ..default()
+        };
+        image.resize(size);
+
+        Self {
+            image: world.resource_mut::<Assets<Image>>().add(image),
+            scale: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct UpscaleCamera;
+
+/// Sprite showing the low resolution target on the upscaling camera.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct UpscaleSprite;
+
+pub fn spawn_upscale_camera(mut commands: Commands, target: Res<PixelPerfectTarget>) {
+    commands.spawn((
+        Name::new("Upscale Camera"),
+        Camera2dBundle {
+            camera: Camera {
+                order: 1,
+                ..default()
+            },
+            ..default()
+        },
+        RenderLayers::layer(UPSCALE_LAYER),
+        UpscaleCamera,
+    ));
+
+    commands.spawn((
+        Name::new("Upscaled World"),
+        SpriteBundle {
+            texture: target.image.clone(),
+            ..default()
+        },
+        RenderLayers::layer(UPSCALE_LAYER),
+        UpscaleSprite,
+    ));
+}
+
+/// Points the world camera at the window or at the low resolution target
+/// and keeps the target sized to the window.
+pub fn sync_render_target(
+    mut world_cameras: Query<
+        (
+            &mut Camera,
+            &mut OrthographicProjection,
+            &mut UiCameraConfig,
+            &CameraMotor,
+        ),
+        Without<UpscaleCamera>,
+    >,
+    mut upscale_cameras: Query<&mut Camera, With<UpscaleCamera>>,
+    mut upscale_sprites: Query<(&mut Transform, &mut Visibility), With<UpscaleSprite>>,
+    mut target: ResMut<PixelPerfectTarget>,
+    mut images: ResMut<Assets<Image>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    options: Res<CameraOptions>,
+) {
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+
+    let zoom = world_cameras
+        .iter()
+        .next()
+        .map_or(options.zoom, |(.., motor)| motor.view_zoom);
+    let scale = zoom.round().max(1.) as u32;
+    let size = Extent3d {
+        width: (window.width() / scale as f32).ceil() as u32 + TARGET_MARGIN,
+        height: (window.height() / scale as f32).ceil() as u32 + TARGET_MARGIN,
+        depth_or_array_layers: 1,
+    };
+
+    if options.pixel_perfect {
+        target.scale = scale;
+        if let Some(image) = images.get(&target.image) {
+            if image.texture_descriptor.size != size {
+                if let Some(image) = images.get_mut(&target.image) {
+                    image.resize(size);
+                }
+            }
+        }
+    }
+
+    for (mut camera, mut projection, mut ui, motor) in world_cameras.iter_mut() {
+        let render_target = if options.pixel_perfect {
+            RenderTarget::Image(target.image.clone())
+        } else {
+            RenderTarget::Window(WindowRef::Primary)
+        };
+        let retarget = match (&camera.target, &render_target) {
+            (RenderTarget::Image(current), RenderTarget::Image(image)) => current != image,
+            (
+                RenderTarget::Window(WindowRef::Primary),
+                RenderTarget::Window(WindowRef::Primary),
+            ) => false,
+            _ => true,
+        };
+        if retarget {
+            camera.target = render_target;
+        }
+
+        projection.scale = if options.pixel_perfect {
+            1.
+        } else {
+            1. / motor.view_zoom
+        };
+        ui.show_ui = !options.pixel_perfect;
+    }
+
+    for mut camera in upscale_cameras.iter_mut() {
+        camera.is_active = options.pixel_perfect;
+    }
+
+    for (mut transform, mut visibility) in upscale_sprites.iter_mut() {
+        transform.scale = Vec3::new(scale as f32, scale as f32, 1.);
+        *visibility = if options.pixel_perfect {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// Snaps the world camera to whole texels and moves the upscaled image by
+/// the remainder instead, so following stays smooth without shimmering.
+pub fn snap_camera_to_pixels(
+    mut world_cameras: Query<&mut Transform, (With<CameraMotor>, Without<UpscaleSprite>)>,
+    mut upscale_sprites: Query<&mut Transform, With<UpscaleSprite>>,
+    target: Res<PixelPerfectTarget>,
+    options: Res<CameraOptions>,
+) {
+    if !options.pixel_perfect {
+        return;
+    }
+
+    let Ok(mut camera_transform) = world_cameras.get_single_mut() else {
+        return;
+    };
+
+    let exact = camera_transform.translation.truncate();
+    let snapped = exact.round();
+    camera_transform.translation = snapped.extend(camera_transform.translation.z);
+
+    for mut sprite_transform in upscale_sprites.iter_mut() {
+        let offset = (snapped - exact) * target.scale as f32;
+        sprite_transform.translation = offset.extend(0.);
+    }
+}
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+use super::{CameraOptions, PlayerAttackEvent, PlayerMarker};
+use crate::{core::DamageTakenEvent, noise};
+
+/// Asks the camera to shake. `trauma` adds up and is capped at 1, `kick`
+/// pushes the camera by that many pixels before it springs back.
+#[derive(Debug, Default, Clone, Copy, Event)]
+pub struct CameraShakeEvent {
+    pub trauma: f32,
+    pub kick: Vec2,
+}
+
+/// Trauma based screen shake: the shake grows with the square of `trauma`,
+/// which decays over time, so small hits barely register while big ones rattle.
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct CameraShake {
+    pub trauma: f32,
+    /// Trauma lost per second.
+    pub decay: f32,
+    /// Offset in pixels at full trauma.
+    pub max_offset: Vec2,
+    /// Roll in radians at full trauma.
+    pub max_roll: f32,
+    /// How fast the noise driving the shake changes.
+    pub frequency: f32,
+    pub kick: Vec2,
+    /// How quickly a kick returns to rest, higher is snappier.
+    pub kick_recovery: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.,
+            decay: 1.5,
+            max_offset: Vec2::splat(6.),
+            max_roll: 0.05,
+            frequency: 25.,
+            kick: Vec2::ZERO,
+            kick_recovery: 18.,
+        }
+    }
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).clamp(0., 1.);
+    }
+}
+
+pub fn shake_on_damage(
+    mut damage_events: EventReader<DamageTakenEvent>,
+    mut shake_events: EventWriter<CameraShakeEvent>,
+    heroes: Query<(), With<PlayerMarker>>,
+    options: Res<CameraOptions>,
+) {
+    for event in damage_events.iter() {
+        let trauma = if heroes.contains(event.taken_by) {
+            options.hero_damage_trauma
+        } else if event.killing_blow {
+            options.killing_blow_trauma
+        } else {
+            options.enemy_damage_trauma
+        };
+
+        shake_events.send(CameraShakeEvent {
+            trauma,
+            kick: Vec2::ZERO,
+        });
+    }
+}
+
+pub fn kick_on_attack(
+    mut attack_events: EventReader<PlayerAttackEvent>,
+    mut shake_events: EventWriter<CameraShakeEvent>,
+    options: Res<CameraOptions>,
+) {
+    for event in attack_events.iter() {
+        shake_events.send(CameraShakeEvent {
+            trauma: 0.,
+            kick: event.direction * options.attack_kick,
+        });
+    }
+}
+
+pub fn apply_camera_shake(
+    mut cameras: Query<(&mut Transform, &mut CameraShake)>,
+    mut events: EventReader<CameraShakeEvent>,
+    time: Res<Time>,
+    options: Res<CameraOptions>,
+) {
+    let events: Vec<_> = events.iter().copied().collect();
+    let dt = time.delta_seconds();
+    let t = time.elapsed_seconds();
+
+    for (mut transform, mut shake) in cameras.iter_mut() {
+        if !options.screen_shake {
+            shake.trauma = 0.;
+            shake.kick = Vec2::ZERO;
+            transform.rotation = Quat::IDENTITY;
+            continue;
+        }
+
+        for event in events.iter() {
+            shake.add_trauma(event.trauma);
+            shake.kick += event.kick;
+        }
+
+        let amount = shake.trauma.powi(2) * options.shake_intensity;
+        let offset = Vec2::new(noise(t * shake.frequency, 0), noise(t * shake.frequency, 1))
+            * shake.max_offset
+            * amount;
+        let roll = noise(t * shake.frequency, 2) * shake.max_roll * amount;
+
+        transform.translation += (offset + shake.kick * options.shake_intensity).extend(0.);
+        transform.rotation = Quat::from_rotation_z(roll);
+
+        shake.trauma = (shake.trauma - shake.decay * dt).max(0.);
+        let recovery = (-shake.kick_recovery * dt).exp();
+        shake.kick *= recovery;
+    }
+}
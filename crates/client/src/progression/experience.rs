@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+
+use super::ProgressionOptions;
+use crate::core::DamageTakenEvent;
+
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct ExperienceReward(pub u32);
+
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct Level {
+    pub level: u32,
+    pub experience: u32,
+    pub unspent_upgrades: u32,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            experience: 0,
+            unspent_upgrades: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct ExperienceCurve {
+    pub base: u32,
+    pub growth: f32,
+}
+
+impl ExperienceCurve {
+    /// Experience needed to advance from `level` to the next one.
+    pub fn required_for(&self, level: u32) -> u32 {
+        let required = self.base as f32 * self.growth.powi(level.saturating_sub(1) as i32);
+        (required.round() as u32).max(1)
+    }
+}
+
+impl Default for ExperienceCurve {
+    fn default() -> Self {
+        Self {
+            base: 5,
+            growth: 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Event, Clone, Copy)]
+pub struct LevelUpEvent {
+    pub entity: Entity,
+}
+
+pub fn grant_experience(
+    rewards: Query<&ExperienceReward>,
+    mut levels: Query<&mut Level>,
+    mut damage_taken: EventReader<DamageTakenEvent>,
+    mut level_ups: EventWriter<LevelUpEvent>,
+    options: Res<ProgressionOptions>,
+) {
+    for DamageTakenEvent {
+        taken_by,
+        dealt_by,
+        killing_blow,
+        ..
+    } in damage_taken.into_iter()
+    {
+        if !killing_blow {
+            continue;
+        }
+
+        let (Some(dealt_by), Ok(ExperienceReward(reward))) = (dealt_by, rewards.get(*taken_by))
+        else {
+            continue;
+        };
+
+        let Ok(mut level) = levels.get_mut(*dealt_by) else {
+            continue;
+        };
+
+        level.experience += reward;
+        loop {
+            let required = options.experience_curve.required_for(level.level);
+            if level.experience < required {
+                break;
+            }
+
+            level.experience -= required;
+            level.level += 1;
+            level.unspent_upgrades += 1;
+
+            level_ups.send(LevelUpEvent { entity: *dealt_by });
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier2d::prelude::RapierConfiguration;
+
+use super::{Level, ProgressionOptions, Upgrade, Upgrades};
+use crate::{
+    core::{HealthChangedEvent, HealthPool},
+    level::DungeonRun,
+    player::{AttackStats, PlayerMotor},
+};
+
+/// Present while the game waits for the player to pick an upgrade.
+/// Gameplay systems do not run as long as this resource exists.
+#[derive(Debug, Resource)]
+pub struct PendingLevelUp {
+    pub entity: Entity,
+    pub choices: Vec<Upgrade>,
+}
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct ProgressionRng(pub fastrand::Rng);
+
+impl Default for ProgressionRng {
+    fn default() -> Self {
+        Self(fastrand::Rng::new())
+    }
+}
+
+impl ProgressionRng {
+    /// Upgrade draws of a run, kept apart from the floors generated from
+    /// the same seed.
+    pub fn for_run(run: &DungeonRun) -> Self {
+        Self(fastrand::Rng::with_seed(run.seed ^ 0x5DEE_CE66_D1CE_4E5B))
+    }
+}
+
+/// Game time and physics, held still while an upgrade is picked.
+#[derive(SystemParam)]
+pub struct GameClock<'w> {
+    time: ResMut<'w, Time>,
+    physics: ResMut<'w, RapierConfiguration>,
+}
+
+impl<'w> GameClock<'w> {
+    pub fn pause(&mut self) {
+        self.time.pause();
+        self.physics.physics_pipeline_active = false;
+    }
+
+    pub fn unpause(&mut self) {
+        self.time.unpause();
+        self.physics.physics_pipeline_active = true;
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct LevelUpScreen;
+
+#[derive(Debug, Clone, Copy, Component)]
+pub struct UpgradeChoiceButton(pub usize);
+
+/// Clicks on the choice buttons and presses of the number keys.
+#[derive(SystemParam)]
+pub struct UpgradeChoiceInput<'w, 's> {
+    buttons:
+        Query<'w, 's, (&'static Interaction, &'static UpgradeChoiceButton), Changed<Interaction>>,
+    keys: Res<'w, Input<KeyCode>>,
+}
+
+impl UpgradeChoiceInput<'_, '_> {
+    /// The choice picked this frame, out of the first `count`.
+    pub fn picked(&self, count: usize) -> Option<usize> {
+        let clicked = self
+            .buttons
+            .iter()
+            .find(|(interaction, _)| **interaction == Interaction::Pressed)
+            .map(|(_, UpgradeChoiceButton(idx))| *idx);
+        let pressed = CHOICE_KEYS
+            .iter()
+            .take(count)
+            .position(|key| self.keys.just_pressed(*key));
+
+        clicked.or(pressed)
+    }
+}
+
+const CHOICE_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+pub fn offer_level_up(
+    mut commands: Commands,
+    mut levels: Query<(Entity, &mut Level)>,
+    mut rng: ResMut<ProgressionRng>,
+    mut clock: GameClock,
+    pending: Option<Res<PendingLevelUp>>,
+    upgrades: Upgrades,
+    options: Res<ProgressionOptions>,
+) {
+    if pending.is_some() {
+        return;
+    }
+
+    let Some(pool) = upgrades.pool() else {
+        return;
+    };
+
+    let Some((entity, mut level)) = levels
+        .iter_mut()
+        .find(|(_, level)| level.unspent_upgrades > 0)
+    else {
+        return;
+    };
+
+    let choices = pool.draw(options.upgrade_choices.min(CHOICE_KEYS.len()), &mut rng);
+    if choices.is_empty() {
+        return;
+    }
+
+    level.unspent_upgrades -= 1;
+
+    spawn_level_up_screen(&mut commands, level.level, &choices);
+    commands.insert_resource(PendingLevelUp { entity, choices });
+    clock.pause();
+}
+
+fn spawn_level_up_screen(commands: &mut Commands, level: u32, choices: &[Upgrade]) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.),
+                    ..default()
+                },
+                background_color: Color::rgba_u8(0x0A, 0x0D, 0x11, 0xC0).into(),
+                ..default()
+            },
+            LevelUpScreen,
+            Name::new("Level Up Screen"),
+        ))
+        .with_children(|screen| {
+            screen.spawn(TextBundle::from_section(
+                format!("Level {level}"),
+                TextStyle {
+                    font_size: 48.,
+                    color: Color::rgb_u8(0xC8, 0xAC, 0x93),
+                    ..default()
+                },
+            ));
+
+            for (idx, upgrade) in choices.iter().enumerate() {
+                screen
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(360.),
+                                flex_direction: FlexDirection::Column,
+                                padding: UiRect::all(Val::Px(8.)),
+                                ..default()
+                            },
+                            background_color: Color::rgb_u8(0x2D, 0x29, 0x1C).into(),
+                            ..default()
+                        },
+                        UpgradeChoiceButton(idx),
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle::from_section(
+                            format!("[{}] {}", idx + 1, upgrade.name),
+                            TextStyle {
+                                font_size: 24.,
+                                color: Color::rgb_u8(0xC8, 0xAC, 0x93),
+                                ..default()
+                            },
+                        ));
+                        button.spawn(TextBundle::from_section(
+                            upgrade.description.clone(),
+                            TextStyle {
+                                font_size: 16.,
+                                color: Color::rgb_u8(0xB1, 0x74, 0x3D),
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+pub fn choose_upgrade(
+    mut commands: Commands,
+    mut targets: Query<(
+        Option<&mut HealthPool>,
+        Option<&mut PlayerMotor>,
+        Option<&mut AttackStats>,
+    )>,
+    input: UpgradeChoiceInput,
+    screens: Query<Entity, With<LevelUpScreen>>,
+    mut health_changed: EventWriter<HealthChangedEvent>,
+    mut clock: GameClock,
+    pending: Option<Res<PendingLevelUp>>,
+) {
+    let Some(pending) = pending else {
+        return;
+    };
+
+    let picked = input.picked(pending.choices.len());
+    let Some(upgrade) = picked.and_then(|idx| pending.choices.get(idx)) else {
+        return;
+    };
+
+    if let Ok((mut hp, mut motor, mut attack)) = targets.get_mut(pending.entity) {
+        let before = hp.as_ref().map(|hp| (hp.current_hp, hp.max_hp));
+        for modifier in upgrade.modifiers.iter() {
+            modifier.apply(
+                hp.as_deref_mut(),
+                motor.as_deref_mut(),
+                attack.as_deref_mut(),
+            );
+        }
+
+        if let (Some(hp), Some((previous_hp, previous_max))) = (hp.as_ref(), before) {
+            if (hp.current_hp, hp.max_hp) != (previous_hp, previous_max) {
+                health_changed.send(HealthChangedEvent::new(pending.entity, previous_hp, hp));
+            }
+        }
+    }
+
+    for screen in screens.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+
+    commands.remove_resource::<PendingLevelUp>();
+    clock.unpause();
+}
@@ -0,0 +1,84 @@
+mod experience;
+mod level_up;
+mod upgrades;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_inspector_egui::{prelude::ReflectInspectorOptions, InspectorOptions};
+
+pub use experience::*;
+pub use level_up::*;
+pub use upgrades::*;
+
+use crate::{
+    core::{damage_system, GameplaySet},
+    state::GameState,
+};
+
+pub struct ProgressionPlugin;
+
+impl Plugin for ProgressionPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ProgressionOptions>()
+            .register_type::<Level>()
+            .register_type::<ExperienceReward>()
+            .init_resource::<ProgressionOptions>()
+            .init_resource::<ProgressionRng>()
+            .add_asset::<UpgradePool>()
+            .init_asset_loader::<UpgradePoolLoader>()
+            .init_resource::<UpgradeLibrary>()
+            .add_event::<LevelUpEvent>()
+            .configure_set(
+                Update,
+                GameplaySet.run_if(not(resource_exists::<PendingLevelUp>())),
+            )
+            .add_systems(
+                Update,
+                (
+                    grant_experience.after(damage_system).in_set(GameplaySet),
+                    (offer_level_up.after(grant_experience), choose_upgrade)
+                        .run_if(in_state(GameState::Playing)),
+                ),
+            );
+    }
+}
+
+#[derive(Resource, Reflect, InspectorOptions)]
+#[reflect(Resource, InspectorOptions)]
+pub struct ProgressionOptions {
+    pub experience_curve: ExperienceCurve,
+    pub upgrade_choices: usize,
+}
+
+impl Default for ProgressionOptions {
+    fn default() -> Self {
+        Self {
+            experience_curve: ExperienceCurve::default(),
+            upgrade_choices: 3,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct UpgradeLibrary {
+    pub pool: Handle<UpgradePool>,
+}
+
+impl FromWorld for UpgradeLibrary {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            pool: world.resource::<AssetServer>().load("default.upgrades.ron"),
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct Upgrades<'w> {
+    library: Res<'w, UpgradeLibrary>,
+    pools: Res<'w, Assets<UpgradePool>>,
+}
+
+impl<'w> Upgrades<'w> {
+    pub fn pool(&self) -> Option<&UpgradePool> {
+        self.pools.get(&self.library.pool)
+    }
+}
@@ -0,0 +1,116 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{
+    core::HealthPool,
+    player::{AttackStats, PlayerMotor},
+};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum StatModifier {
+    MaxHealth(u32),
+    Heal(u32),
+    MoveSpeed(f32),
+    Acceleration(f32),
+    AttackDamage(u32),
+    AttackRange(f32),
+}
+
+impl StatModifier {
+    pub fn apply(
+        &self,
+        hp: Option<&mut HealthPool>,
+        motor: Option<&mut PlayerMotor>,
+        attack: Option<&mut AttackStats>,
+    ) {
+        use StatModifier::*;
+
+        match (*self, hp, motor, attack) {
+            (MaxHealth(amount), Some(hp), _, _) => {
+                hp.max_hp += amount;
+                hp.current_hp += amount;
+            }
+            (Heal(amount), Some(hp), _, _) => {
+                hp.current_hp = (hp.current_hp + amount).min(hp.max_hp);
+            }
+            (MoveSpeed(factor), _, Some(motor), _) => motor.max_speed *= factor,
+            (Acceleration(factor), _, Some(motor), _) => motor.max_accel *= factor,
+            (AttackDamage(amount), _, _, Some(attack)) => attack.damage += amount,
+            (AttackRange(factor), _, _, Some(attack)) => attack.range *= factor,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Upgrade {
+    pub name: String,
+    pub description: String,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    pub modifiers: Vec<StatModifier>,
+}
+
+fn default_weight() -> f32 {
+    1.
+}
+
+#[derive(Debug, Deserialize, TypeUuid, TypePath)]
+#[uuid = "6f0b6e3c-3f5e-4b1e-9a55-2d1f3c2a7b10"]
+pub struct UpgradePool {
+    pub upgrades: Vec<Upgrade>,
+}
+
+impl UpgradePool {
+    /// Draws up to `count` distinct upgrades, weighted by [`Upgrade::weight`].
+    pub fn draw(&self, count: usize, rng: &mut fastrand::Rng) -> Vec<Upgrade> {
+        let mut candidates: Vec<&Upgrade> = self
+            .upgrades
+            .iter()
+            .filter(|upgrade| upgrade.weight > 0.)
+            .collect();
+        let mut drawn = Vec::with_capacity(count);
+
+        while drawn.len() < count && !candidates.is_empty() {
+            let total: f32 = candidates.iter().map(|upgrade| upgrade.weight).sum();
+            let mut roll = rng.f32() * total;
+
+            let idx = candidates
+                .iter()
+                .position(|upgrade| {
+                    roll -= upgrade.weight;
+                    roll <= 0.
+                })
+                .unwrap_or(candidates.len() - 1);
+
+            drawn.push(candidates.swap_remove(idx).clone());
+        }
+
+        drawn
+    }
+}
+
+#[derive(Default)]
+pub struct UpgradePoolLoader;
+
+impl AssetLoader for UpgradePoolLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let pool = ron::de::from_bytes::<UpgradePool>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(pool));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["upgrades.ron"]
+    }
+}
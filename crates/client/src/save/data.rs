@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::HealthPool,
+    player::{AttackStats, PlayerMotor},
+    progression::Level,
+};
+
+/// Everything needed to pick a run back up. Positions are stored as
+/// plain arrays so the format does not depend on math library features.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveData {
+    pub run: SavedRun,
+    pub rng: SavedRng,
+    pub hero: SavedHero,
+    pub world: SavedWorld,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedRun {
+    pub seed: u64,
+    pub floor: u32,
+}
+
+/// State of the random number generators gameplay owns. Cosmetic effects
+/// draw from the thread-local generator, which is not saved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedRng {
+    pub progression: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedHealth {
+    pub current_hp: u32,
+    pub max_hp: u32,
+}
+
+impl From<&HealthPool> for SavedHealth {
+    fn from(pool: &HealthPool) -> Self {
+        Self {
+            current_hp: pool.current_hp,
+            max_hp: pool.max_hp,
+        }
+    }
+}
+
+impl From<&SavedHealth> for HealthPool {
+    fn from(health: &SavedHealth) -> Self {
+        HealthPool {
+            max_hp: health.max_hp,
+            current_hp: health.current_hp.min(health.max_hp),
+            just_died: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedHero {
+    pub position: [f32; 2],
+    pub health: SavedHealth,
+    pub level: u32,
+    pub experience: u32,
+    pub unspent_upgrades: u32,
+    pub attack_damage: u32,
+    pub attack_range: f32,
+    pub max_speed: f32,
+    pub max_accel: f32,
+}
+
+impl SavedHero {
+    pub fn new(
+        transform: &Transform,
+        hp: &HealthPool,
+        level: &Level,
+        attack: &AttackStats,
+        motor: &PlayerMotor,
+    ) -> Self {
+        Self {
+            position: transform.translation.truncate().to_array(),
+            health: hp.into(),
+            level: level.level,
+            experience: level.experience,
+            unspent_upgrades: level.unspent_upgrades,
+            attack_damage: attack.damage,
+            attack_range: attack.range,
+            max_speed: motor.max_speed,
+            max_accel: motor.max_accel,
+        }
+    }
+
+    pub fn apply(
+        &self,
+        transform: &mut Transform,
+        hp: &mut HealthPool,
+        level: &mut Level,
+        attack: &mut AttackStats,
+        motor: &mut PlayerMotor,
+    ) {
+        transform.translation = Vec2::from(self.position).extend(transform.translation.z);
+        *hp = (&self.health).into();
+        *level = Level {
+            level: self.level,
+            experience: self.experience,
+            unspent_upgrades: self.unspent_upgrades,
+        };
+        attack.damage = self.attack_damage;
+        attack.range = self.attack_range;
+        motor.max_speed = self.max_speed;
+        motor.max_accel = self.max_accel;
+        motor.velocity = Vec2::ZERO;
+    }
+}
+
+/// Entities of the current floor whose state changes during play. The
+/// floor itself is generated again from the run's seed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedWorld {
+    pub dummies: Vec<SavedDummy>,
+    pub corpses: Vec<[f32; 2]>,
+    pub chests: Vec<SavedChest>,
+    pub spawners: Vec<SavedSpawner>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedDummy {
+    pub position: [f32; 2],
+    pub health: SavedHealth,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedChest {
+    pub position: [f32; 2],
+    pub opened: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSpawner {
+    pub position: [f32; 2],
+    pub remaining: u32,
+    pub cooldown_elapsed: f32,
+    /// Index into [`SavedWorld::dummies`] of the dummy the spawner keeps alive.
+    pub alive: Option<usize>,
+}
@@ -0,0 +1,47 @@
+use serde_json::Value;
+
+use super::{SaveData, SaveError};
+
+/// Version written into new saves. Bump it whenever [`SaveData`] changes
+/// shape and add a migration from the previous version to [`MIGRATIONS`].
+pub const SAVE_VERSION: u32 = 1;
+
+/// Upgrades a save from the version it is keyed by to the next one,
+/// working on the raw JSON so old shapes need no Rust types.
+type Migration = fn(&mut Value) -> Result<(), SaveError>;
+
+/// Migrations in order, keyed by the version they upgrade from.
+const MIGRATIONS: &[(u32, Migration)] = &[];
+
+pub fn encode_save(data: &SaveData) -> Result<String, SaveError> {
+    let mut value = serde_json::to_value(data)?;
+    value["version"] = SAVE_VERSION.into();
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+/// Reads a save of any supported version, migrating it to the current one.
+pub fn decode_save(text: &str) -> Result<SaveData, SaveError> {
+    let mut value: Value = serde_json::from_str(text)?;
+    let mut version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or(SaveError::MissingVersion)? as u32;
+
+    if version == 0 || version > SAVE_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+
+    for (from, migrate) in MIGRATIONS.iter() {
+        if *from == version {
+            migrate(&mut value)?;
+            version += 1;
+            value["version"] = version.into();
+        }
+    }
+
+    if version != SAVE_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+
+    Ok(serde_json::from_value(value)?)
+}
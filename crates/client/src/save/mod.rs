@@ -0,0 +1,422 @@
+mod data;
+mod format;
+mod storage;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+pub use data::*;
+pub use format::*;
+pub use storage::*;
+
+use crate::{
+    content::{
+        spawn_dummy, Chest, ChestBundle, DummyBehaviour, DummyCorpse, DummyCorpseBundle,
+        DummySpawner, DummySpawnerBundle,
+    },
+    core::{HealthChangedEvent, HealthPool},
+    level::{
+        spawn_level, CurrentLevel, DungeonRun, DungeonSettings, LevelAsset, LevelEntity, TileMap,
+    },
+    player::{AttackStats, PlayerMarker, PlayerMotor},
+    progression::{Level, ProgressionRng},
+    state::{begin_new_run, start_run, GameState},
+};
+
+const SAVE_KEY: &str = "save.json";
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SaveStorage>()
+            .add_event::<SaveGameEvent>()
+            .add_event::<LoadGameEvent>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                restore_run
+                    .after(start_run)
+                    .run_if(resource_exists::<PendingRestore>()),
+            )
+            .add_systems(OnEnter(GameState::MainMenu), cancel_restore)
+            .add_systems(
+                Update,
+                (
+                    save_game,
+                    load_game,
+                    restore_world
+                        .after(spawn_level)
+                        .run_if(resource_exists::<PendingRestore>()),
+                ),
+            );
+    }
+}
+
+/// Writes the running game to storage.
+#[derive(Debug, Default, Clone, Copy, Event)]
+pub struct SaveGameEvent;
+
+/// Replaces the running game, if any, with the one in storage.
+#[derive(Debug, Default, Clone, Copy, Event)]
+pub struct LoadGameEvent;
+
+/// A loaded save waiting to be applied. The run is restored when play
+/// starts, the hero and world once the saved floor has been spawned.
+#[derive(Debug, Resource)]
+pub struct PendingRestore(pub SaveData);
+
+/// The entities of a floor that saves keep track of.
+#[derive(SystemParam)]
+pub struct SavedEntities<'w, 's> {
+    dummies: Query<'w, 's, (Entity, &'static Transform, &'static HealthPool), With<DummyBehaviour>>,
+    corpses: Query<'w, 's, &'static Transform, With<DummyCorpse>>,
+    chests: Query<'w, 's, (&'static Transform, &'static Chest)>,
+    spawners: Query<'w, 's, (&'static Transform, &'static DummySpawner)>,
+}
+
+impl SavedEntities<'_, '_> {
+    pub fn save(&self) -> SavedWorld {
+        let dummy_entities: Vec<_> = self.dummies.iter().map(|(entity, ..)| entity).collect();
+        let position = |transform: &Transform| transform.translation.truncate().to_array();
+
+        SavedWorld {
+            dummies: self
+                .dummies
+                .iter()
+                .map(|(_, transform, hp)| SavedDummy {
+                    position: position(transform),
+                    health: hp.into(),
+                })
+                .collect(),
+            corpses: self.corpses.iter().map(position).collect(),
+            chests: self
+                .chests
+                .iter()
+                .map(|(transform, chest)| SavedChest {
+                    position: position(transform),
+                    opened: chest.opened,
+                })
+                .collect(),
+            spawners: self
+                .spawners
+                .iter()
+                .map(|(transform, spawner)| SavedSpawner {
+                    position: position(transform),
+                    remaining: spawner.remaining,
+                    cooldown_elapsed: spawner.cooldown.elapsed_secs(),
+                    alive: spawner
+                        .alive
+                        .and_then(|alive| dummy_entities.iter().position(|&e| e == alive)),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn save_game(
+    mut events: EventReader<SaveGameEvent>,
+    heroes: Query<
+        (&Transform, &HealthPool, &Level, &AttackStats, &PlayerMotor),
+        With<PlayerMarker>,
+    >,
+    entities: SavedEntities,
+    run: Res<DungeonRun>,
+    rng: Res<ProgressionRng>,
+    storage: Res<SaveStorage>,
+    pending: Option<Res<PendingRestore>>,
+) {
+    if events.iter().count() == 0 {
+        return;
+    }
+
+    if pending.is_some() {
+        warn!("Not saving while a save is still being restored");
+        return;
+    }
+
+    let Ok((transform, hp, level, attack, motor)) = heroes.get_single() else {
+        warn!("Not saving without a hero");
+        return;
+    };
+
+    let data = SaveData {
+        run: SavedRun {
+            seed: run.seed,
+            floor: run.floor,
+        },
+        rng: SavedRng {
+            progression: rng.get_seed(),
+        },
+        hero: SavedHero::new(transform, hp, level, attack, motor),
+        world: entities.save(),
+    };
+
+    match encode_save(&data).and_then(|text| storage.write(SAVE_KEY, &text)) {
+        Ok(()) => info!("Saved floor {} of run {:#x}", run.floor, run.seed),
+        Err(err) => error!("Could not save the game: {err}"),
+    }
+}
+
+pub fn load_game(
+    mut commands: Commands,
+    mut events: EventReader<LoadGameEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+    storage: Res<SaveStorage>,
+) {
+    if events.iter().count() == 0 {
+        return;
+    }
+
+    let data = match storage.read(SAVE_KEY) {
+        Ok(Some(text)) => decode_save(&text),
+        Ok(None) => {
+            warn!("There is no saved game to load");
+            return;
+        }
+        Err(err) => Err(err),
+    };
+
+    match data {
+        Ok(data) => {
+            commands.insert_resource(PendingRestore(data));
+            begin_new_run(&mut commands, &mut next_state);
+        }
+        Err(err) => error!("Could not load the game: {err}"),
+    }
+}
+
+pub fn restore_run(
+    pending: Res<PendingRestore>,
+    mut run: ResMut<DungeonRun>,
+    mut current: ResMut<CurrentLevel>,
+    mut rng: ResMut<ProgressionRng>,
+    mut levels: ResMut<Assets<LevelAsset>>,
+    settings: Res<DungeonSettings>,
+    asset_server: Res<AssetServer>,
+) {
+    let PendingRestore(data) = pending.as_ref();
+
+    *run = DungeonRun {
+        seed: data.run.seed,
+        floor: data.run.floor,
+    };
+    *current = run.floor_level(&settings, &asset_server, &mut levels);
+    *rng = ProgressionRng(fastrand::Rng::with_seed(data.rng.progression));
+}
+
+type RestoredHeroQuery<'a> = (
+    Entity,
+    &'a mut Transform,
+    &'a mut HealthPool,
+    &'a mut Level,
+    &'a mut AttackStats,
+    &'a mut PlayerMotor,
+);
+
+type SavedEntityFilter = (
+    With<LevelEntity>,
+    Or<(
+        With<DummyBehaviour>,
+        With<DummyCorpse>,
+        With<Chest>,
+        With<DummySpawner>,
+    )>,
+);
+
+/// Puts the saved hero and entities back once the saved floor is spawned,
+/// replacing the entities the floor starts out with.
+pub fn restore_world(
+    mut commands: Commands,
+    mut heroes: Query<RestoredHeroQuery, With<PlayerMarker>>,
+    spawned: Query<Entity, SavedEntityFilter>,
+    levels: Query<(), With<TileMap>>,
+    pending: Res<PendingRestore>,
+    mut health_changed: EventWriter<HealthChangedEvent>,
+) {
+    if levels.is_empty() {
+        return;
+    }
+
+    let Ok((hero, mut transform, mut hp, mut level, mut attack, mut motor)) =
+        heroes.get_single_mut()
+    else {
+        return;
+    };
+
+    let PendingRestore(data) = pending.as_ref();
+
+    let previous_hp = hp.current_hp;
+    data.hero
+        .apply(&mut transform, &mut hp, &mut level, &mut attack, &mut motor);
+    health_changed.send(HealthChangedEvent::new(hero, previous_hp, &hp));
+
+    for entity in spawned.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let transform_at =
+        |position: [f32; 2]| Transform::from_translation(Vec2::from(position).extend(0.));
+
+    let dummies: Vec<_> = data
+        .world
+        .dummies
+        .iter()
+        .map(|dummy| {
+            let entity = spawn_dummy(&mut commands, transform_at(dummy.position));
+            commands
+                .entity(entity)
+                .insert((LevelEntity, HealthPool::from(&dummy.health)));
+            entity
+        })
+        .collect();
+
+    for &position in data.world.corpses.iter() {
+        commands.spawn((DummyCorpseBundle::new(transform_at(position)), LevelEntity));
+    }
+
+    for saved in data.world.chests.iter() {
+        let mut chest = ChestBundle::new(transform_at(saved.position));
+        chest.chest.opened = saved.opened;
+        commands.spawn((chest, LevelEntity));
+    }
+
+    for saved in data.world.spawners.iter() {
+        let mut spawner = DummySpawnerBundle::new(transform_at(saved.position));
+        spawner.spawner.remaining = saved.remaining;
+        spawner
+            .spawner
+            .cooldown
+            .set_elapsed(std::time::Duration::from_secs_f32(saved.cooldown_elapsed));
+        spawner.spawner.alive = saved.alive.and_then(|idx| dummies.get(idx).copied());
+        commands.spawn((spawner, LevelEntity));
+    }
+
+    commands.remove_resource::<PendingRestore>();
+}
+
+/// Drops a restore that never got to finish, e.g. when quitting to the
+/// main menu before the saved floor was spawned.
+pub fn cancel_restore(mut commands: Commands) {
+    commands.remove_resource::<PendingRestore>();
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::CommandQueue;
+
+    use super::*;
+    use crate::{
+        hero::spawn_hero,
+        level::TileGrid,
+        state::{start_run, StartRun},
+    };
+
+    fn run_commands<T>(app: &mut App, f: impl FnOnce(&mut Commands) -> T) -> T {
+        let mut queue = CommandQueue::default();
+        let output = f(&mut Commands::new(&mut queue, &app.world));
+        queue.apply(&mut app.world);
+        output
+    }
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .add_state::<GameState>()
+            .add_asset::<LevelAsset>()
+            .add_event::<HealthChangedEvent>()
+            .insert_resource(SaveStorage::in_memory())
+            .init_resource::<DungeonSettings>()
+            .init_resource::<CurrentLevel>()
+            .init_resource::<DungeonRun>()
+            .init_resource::<ProgressionRng>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                start_run.run_if(resource_exists::<StartRun>()),
+            )
+            .add_plugins(SavePlugin);
+
+        // Stands in for the floor the restore waits for.
+        app.world
+            .spawn((TileMap::new(TileGrid::new(UVec2::splat(4))), LevelEntity));
+        app.update();
+        app
+    }
+
+    fn hero_state(app: &mut App) -> (Vec2, u32, u32) {
+        let (transform, hp) = app
+            .world
+            .query_filtered::<(&Transform, &HealthPool), With<PlayerMarker>>()
+            .single(&app.world);
+        (transform.translation.truncate(), hp.current_hp, hp.max_hp)
+    }
+
+    fn dummy_states(app: &mut App) -> Vec<(Vec2, u32)> {
+        let mut dummies: Vec<_> = app
+            .world
+            .query_filtered::<(&Transform, &HealthPool), With<DummyBehaviour>>()
+            .iter(&app.world)
+            .map(|(transform, hp)| (transform.translation.truncate(), hp.current_hp))
+            .collect();
+        dummies.sort_by(|a, b| a.0.x.total_cmp(&b.0.x));
+        dummies
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let mut app = test_app();
+
+        let hero = run_commands(&mut app, spawn_hero);
+        app.world.get_mut::<Transform>(hero).unwrap().translation = Vec3::new(12., -4., 0.);
+        app.world.get_mut::<HealthPool>(hero).unwrap().current_hp = 6;
+
+        let mut spawned = vec![hero];
+        for (position, current_hp) in [(Vec2::new(-24., 40.), 1), (Vec2::new(16., 8.), 3)] {
+            let dummy = run_commands(&mut app, |commands| {
+                let dummy = spawn_dummy(commands, Transform::from_translation(position.extend(0.)));
+                commands.entity(dummy).insert(LevelEntity);
+                dummy
+            });
+            app.world.get_mut::<HealthPool>(dummy).unwrap().current_hp = current_hp;
+            spawned.push(dummy);
+        }
+
+        let run = DungeonRun {
+            seed: 0xC0FFEE,
+            floor: 2,
+        };
+        let mut rng = ProgressionRng::for_run(&run);
+        rng.u64(..);
+        let rng_state = rng.get_seed();
+        app.world.insert_resource(run);
+        app.world.insert_resource(rng);
+
+        let hero_before = hero_state(&mut app);
+        let dummies_before = dummy_states(&mut app);
+
+        app.world.send_event(SaveGameEvent);
+        app.update();
+        assert!(app
+            .world
+            .resource::<SaveStorage>()
+            .read(SAVE_KEY)
+            .unwrap()
+            .is_some());
+
+        for entity in spawned {
+            app.world.entity_mut(entity).despawn_recursive();
+        }
+        app.world.insert_resource(DungeonRun::default());
+        app.world.insert_resource(ProgressionRng::default());
+
+        app.world.send_event(LoadGameEvent);
+        app.update();
+        app.update();
+
+        assert!(!app.world.contains_resource::<PendingRestore>());
+        assert_eq!(hero_state(&mut app), hero_before);
+        assert_eq!(dummy_states(&mut app), dummies_before);
+        assert_eq!(app.world.resource::<ProgressionRng>().get_seed(), rng_state);
+
+        let run = app.world.resource::<DungeonRun>();
+        assert_eq!((run.seed, run.floor), (0xC0FFEE, 2));
+    }
+}
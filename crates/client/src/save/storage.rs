@@ -0,0 +1,153 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use bevy::{prelude::*, utils::HashMap};
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    /// The browser refused access to its storage.
+    #[cfg(target_arch = "wasm32")]
+    Storage(String),
+    Format(serde_json::Error),
+    MissingVersion,
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "could not access save file: {err}"),
+            #[cfg(target_arch = "wasm32")]
+            SaveError::Storage(err) => write!(f, "could not access browser storage: {err}"),
+            SaveError::Format(err) => write!(f, "malformed save: {err}"),
+            SaveError::MissingVersion => write!(f, "save has no format version"),
+            SaveError::UnsupportedVersion(version) => {
+                write!(f, "save format version {version} is not supported")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<std::io::Error> for SaveError {
+    fn from(err: std::io::Error) -> Self {
+        SaveError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(err: serde_json::Error) -> Self {
+        SaveError::Format(err)
+    }
+}
+
+/// Where persistent files live: a directory on native, keys in the
+/// browser's local storage on the web.
+#[derive(Debug, Clone, Resource)]
+pub struct SaveStorage {
+    /// Directory on native, key prefix on the web.
+    pub root: String,
+    /// Files kept in memory instead, shared by all clones of the storage.
+    memory: Option<Arc<Mutex<HashMap<String, String>>>>,
+}
+
+impl Default for SaveStorage {
+    fn default() -> Self {
+        Self {
+            root: "saves".into(),
+            memory: None,
+        }
+    }
+}
+
+impl SaveStorage {
+    /// Storage that never leaves the process, for tests.
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self {
+            memory: Some(default()),
+            ..default()
+        }
+    }
+
+    pub fn read(&self, key: &str) -> Result<Option<String>, SaveError> {
+        match &self.memory {
+            Some(memory) => Ok(memory
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get(key)
+                .cloned()),
+            None => self.read_backend(key),
+        }
+    }
+
+    pub fn write(&self, key: &str, contents: &str) -> Result<(), SaveError> {
+        match &self.memory {
+            Some(memory) => {
+                memory
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .insert(key.into(), contents.into());
+                Ok(())
+            }
+            None => self.write_backend(key, contents),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SaveStorage {
+    fn path(&self, key: &str) -> std::path::PathBuf {
+        std::path::Path::new(&self.root).join(key)
+    }
+
+    fn read_backend(&self, key: &str) -> Result<Option<String>, SaveError> {
+        match std::fs::read_to_string(self.path(key)) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Writes through a temporary file so a crash never leaves a torn save.
+    fn write_backend(&self, key: &str, contents: &str) -> Result<(), SaveError> {
+        let path = self.path(key);
+        let temporary = path.with_extension("tmp");
+
+        std::fs::create_dir_all(&self.root)?;
+        std::fs::write(&temporary, contents)?;
+        std::fs::rename(&temporary, &path)?;
+        Ok(())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl SaveStorage {
+    fn key(&self, key: &str) -> String {
+        format!("{}/{key}", self.root)
+    }
+
+    fn local_storage() -> Result<web_sys::Storage, SaveError> {
+        web_sys::window()
+            .ok_or_else(|| SaveError::Storage("no window".into()))?
+            .local_storage()
+            .map_err(|err| SaveError::Storage(format!("{err:?}")))?
+            .ok_or_else(|| SaveError::Storage("local storage is disabled".into()))
+    }
+
+    fn read_backend(&self, key: &str) -> Result<Option<String>, SaveError> {
+        Self::local_storage()?
+            .get_item(&self.key(key))
+            .map_err(|err| SaveError::Storage(format!("{err:?}")))
+    }
+
+    fn write_backend(&self, key: &str, contents: &str) -> Result<(), SaveError> {
+        Self::local_storage()?
+            .set_item(&self.key(key), contents)
+            .map_err(|err| SaveError::Storage(format!("{err:?}")))
+    }
+}
@@ -0,0 +1,139 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::Settings;
+
+/// Something the player can do that has a rebindable input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Attack,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 6] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Attack,
+        Action::Pause,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Attack => "Attack",
+            Action::Pause => "Pause",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    pub fn just_pressed(&self, keys: &Input<KeyCode>, mouse: &Input<MouseButton>) -> bool {
+        match *self {
+            Binding::Key(key) => keys.just_pressed(key),
+            Binding::Mouse(button) => mouse.just_pressed(button),
+        }
+    }
+}
+
+impl std::fmt::Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key:?}"),
+            Binding::Mouse(button) => write!(f, "Mouse {button:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub move_up: Binding,
+    pub move_down: Binding,
+    pub move_left: Binding,
+    pub move_right: Binding,
+    pub attack: Binding,
+    pub pause: Binding,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            move_up: Binding::Key(KeyCode::W),
+            move_down: Binding::Key(KeyCode::S),
+            move_left: Binding::Key(KeyCode::A),
+            move_right: Binding::Key(KeyCode::D),
+            attack: Binding::Mouse(MouseButton::Left),
+            pause: Binding::Key(KeyCode::Escape),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: Action) -> Binding {
+        match action {
+            Action::MoveUp => self.move_up,
+            Action::MoveDown => self.move_down,
+            Action::MoveLeft => self.move_left,
+            Action::MoveRight => self.move_right,
+            Action::Attack => self.attack,
+            Action::Pause => self.pause,
+        }
+    }
+
+    pub fn set(&mut self, action: Action, binding: Binding) {
+        let slot = match action {
+            Action::MoveUp => &mut self.move_up,
+            Action::MoveDown => &mut self.move_down,
+            Action::MoveLeft => &mut self.move_left,
+            Action::MoveRight => &mut self.move_right,
+            Action::Attack => &mut self.attack,
+            Action::Pause => &mut self.pause,
+        };
+        *slot = binding;
+    }
+}
+
+/// Reads player input through the bindings in [`Settings`].
+#[derive(SystemParam)]
+pub struct Controls<'w> {
+    settings: Res<'w, Settings>,
+    keys: Res<'w, Input<KeyCode>>,
+    mouse: Res<'w, Input<MouseButton>>,
+}
+
+impl<'w> Controls<'w> {
+    pub fn pressed(&self, action: Action) -> bool {
+        match self.settings.controls.get(action) {
+            Binding::Key(key) => self.keys.pressed(key),
+            Binding::Mouse(button) => self.mouse.pressed(button),
+        }
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.settings
+            .controls
+            .get(action)
+            .just_pressed(&self.keys, &self.mouse)
+    }
+
+    /// -1, 0 or 1 along an axis made of two opposing actions.
+    pub fn axis(&self, negative: Action, positive: Action) -> f32 {
+        self.pressed(positive) as i32 as f32 - self.pressed(negative) as i32 as f32
+    }
+}
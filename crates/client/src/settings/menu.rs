@@ -0,0 +1,455 @@
+use bevy::{prelude::*, window::PresentMode};
+
+use super::{Action, Binding, Settings};
+use crate::state::{MenuScreen, BUTTON_COLOR, BUTTON_HOVER_COLOR, TEXT_COLOR, TITLE_COLOR};
+
+const PRESENT_MODES: [PresentMode; 2] = [PresentMode::AutoVsync, PresentMode::AutoNoVsync];
+const WINDOW_SCALES: [Option<f32>; 8] = [
+    None,
+    Some(0.5),
+    Some(0.75),
+    Some(1.),
+    Some(1.25),
+    Some(1.5),
+    Some(2.),
+    Some(3.),
+];
+
+/// Opens the settings menu on top of whatever menu is showing.
+#[derive(Debug, Default, Clone, Copy, Event)]
+pub struct OpenSettingsEvent;
+
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct SettingsScreen;
+
+/// Present while the settings menu waits for the input to bind an action to.
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct Rebinding {
+    pub action: Action,
+    /// Set once the pause binding was pressed, which binds it when pressed
+    /// again and cancels otherwise.
+    pub confirming: bool,
+}
+
+impl Rebinding {
+    pub fn new(action: Action) -> Self {
+        Self {
+            action,
+            confirming: false,
+        }
+    }
+}
+
+/// A setting that is changed by stepping through its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingField {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    PresentMode,
+    WindowScale,
+    FollowSpeed,
+    CursorCenter,
+    CharacterBob,
+    ScreenShake,
+    Zoom,
+}
+
+fn step_value(value: &mut f32, steps: i32, step: f32, min: f32, max: f32) {
+    *value = (((*value / step).round() + steps as f32) * step).clamp(min, max);
+}
+
+fn step_index(len: usize, index: usize, steps: i32) -> usize {
+    (index as i32 + steps).clamp(0, len as i32 - 1) as usize
+}
+
+impl SettingField {
+    pub const ALL: [SettingField; 10] = [
+        SettingField::MasterVolume,
+        SettingField::MusicVolume,
+        SettingField::SfxVolume,
+        SettingField::PresentMode,
+        SettingField::WindowScale,
+        SettingField::FollowSpeed,
+        SettingField::CursorCenter,
+        SettingField::CharacterBob,
+        SettingField::ScreenShake,
+        SettingField::Zoom,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SettingField::MasterVolume => "Master Volume",
+            SettingField::MusicVolume => "Music Volume",
+            SettingField::SfxVolume => "Effects Volume",
+            SettingField::PresentMode => "Present Mode",
+            SettingField::WindowScale => "Window Scale",
+            SettingField::FollowSpeed => "Camera Follow Speed",
+            SettingField::CursorCenter => "Look Towards Cursor",
+            SettingField::CharacterBob => "Character Bob",
+            SettingField::ScreenShake => "Screen Shake",
+            SettingField::Zoom => "Zoom",
+        }
+    }
+
+    pub fn value(&self, settings: &Settings) -> String {
+        let percent = |value: f32| format!("{:.0}%", value * 100.);
+
+        match self {
+            SettingField::MasterVolume => percent(settings.audio.master_volume),
+            SettingField::MusicVolume => percent(settings.audio.music_volume),
+            SettingField::SfxVolume => percent(settings.audio.sfx_volume),
+            SettingField::PresentMode => match settings.video.present_mode {
+                PresentMode::AutoVsync => "VSync".into(),
+                PresentMode::AutoNoVsync => "No VSync".into(),
+                mode => format!("{mode:?}"),
+            },
+            SettingField::WindowScale => match settings.video.window_scale {
+                Some(scale) => format!("{scale}x"),
+                None => "Auto".into(),
+            },
+            SettingField::FollowSpeed => format!("{:.0}", settings.camera.follow_speed),
+            SettingField::CursorCenter => percent(settings.camera.character_to_cursor_center),
+            SettingField::CharacterBob => percent(settings.camera.character_bob_intensity),
+            SettingField::ScreenShake if settings.camera.screen_shake => "On".into(),
+            SettingField::ScreenShake => "Off".into(),
+            SettingField::Zoom => format!("{:.0}x", settings.camera.zoom),
+        }
+    }
+
+    /// Moves the setting `steps` values up or down.
+    pub fn step(&self, settings: &mut Settings, steps: i32) {
+        match self {
+            SettingField::MasterVolume => {
+                step_value(&mut settings.audio.master_volume, steps, 0.1, 0., 1.)
+            }
+            SettingField::MusicVolume => {
+                step_value(&mut settings.audio.music_volume, steps, 0.1, 0., 1.)
+            }
+            SettingField::SfxVolume => {
+                step_value(&mut settings.audio.sfx_volume, steps, 0.1, 0., 1.)
+            }
+            SettingField::PresentMode => {
+                let index = PRESENT_MODES
+                    .iter()
+                    .position(|mode| *mode == settings.video.present_mode)
+                    .unwrap_or_default();
+                settings.video.present_mode =
+                    PRESENT_MODES[step_index(PRESENT_MODES.len(), index, steps)];
+            }
+            SettingField::WindowScale => {
+                let index = WINDOW_SCALES
+                    .iter()
+                    .position(|scale| *scale == settings.video.window_scale)
+                    .unwrap_or_default();
+                settings.video.window_scale =
+                    WINDOW_SCALES[step_index(WINDOW_SCALES.len(), index, steps)];
+            }
+            SettingField::FollowSpeed => {
+                step_value(&mut settings.camera.follow_speed, steps, 5., 5., 60.)
+            }
+            SettingField::CursorCenter => step_value(
+                &mut settings.camera.character_to_cursor_center,
+                steps,
+                0.05,
+                0.,
+                0.5,
+            ),
+            SettingField::CharacterBob => step_value(
+                &mut settings.camera.character_bob_intensity,
+                steps,
+                0.25,
+                0.,
+                1.5,
+            ),
+            SettingField::ScreenShake => {
+                settings.camera.screen_shake = !settings.camera.screen_shake
+            }
+            SettingField::Zoom => step_value(&mut settings.camera.zoom, steps, 1., 2., 8.),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum SettingsButton {
+    Step(SettingField, i32),
+    Rebind(Action),
+    Reset,
+    Back,
+}
+
+/// Text showing the current value of a setting or binding.
+#[derive(Debug, Clone, Copy, Component)]
+pub enum SettingsLabel {
+    Field(SettingField),
+    Binding(Action),
+}
+
+impl SettingsLabel {
+    fn text(&self, settings: &Settings, rebinding: Option<&Rebinding>) -> String {
+        match self {
+            SettingsLabel::Field(field) => field.value(settings),
+            SettingsLabel::Binding(action) => match rebinding {
+                Some(rebinding) if rebinding.action == *action => {
+                    if rebinding.confirming {
+                        "Again to bind it".into()
+                    } else {
+                        "Press a key...".into()
+                    }
+                }
+                _ => settings.controls.get(*action).to_string(),
+            },
+        }
+    }
+}
+
+fn text_style(font_size: f32, color: Color) -> TextStyle {
+    TextStyle {
+        font_size,
+        color,
+        ..default()
+    }
+}
+
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    button: SettingsButton,
+    width: f32,
+    text: impl Into<String>,
+    label: Option<SettingsLabel>,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(width),
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::all(Val::Px(4.)),
+                    ..default()
+                },
+                background_color: BUTTON_COLOR.into(),
+                ..default()
+            },
+            button,
+        ))
+        .with_children(|parent| {
+            let mut text =
+                parent.spawn(TextBundle::from_section(text, text_style(18., TITLE_COLOR)));
+            if let Some(label) = label {
+                text.insert(label);
+            }
+        });
+}
+
+fn spawn_row(
+    parent: &mut ChildBuilder,
+    label: &str,
+    spawn_controls: impl FnOnce(&mut ChildBuilder),
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn(
+                TextBundle::from_section(label, text_style(18., TEXT_COLOR)).with_style(Style {
+                    width: Val::Px(200.),
+                    ..default()
+                }),
+            );
+            spawn_controls(row);
+        });
+}
+
+fn spawn_settings_screen(commands: &mut Commands, settings: &Settings) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.),
+                    ..default()
+                },
+                background_color: Color::rgb_u8(0x0A, 0x0D, 0x11).into(),
+                z_index: ZIndex::Global(20),
+                ..default()
+            },
+            SettingsScreen,
+            MenuScreen,
+            Name::new("Settings Screen"),
+        ))
+        .with_children(|screen| {
+            screen.spawn(TextBundle::from_section(
+                "Settings",
+                text_style(36., TITLE_COLOR),
+            ));
+
+            for field in SettingField::ALL {
+                spawn_row(screen, field.label(), |row| {
+                    spawn_button(row, SettingsButton::Step(field, -1), 32., "<", None);
+                    row.spawn((
+                        TextBundle::from_section(
+                            field.value(settings),
+                            text_style(18., TITLE_COLOR),
+                        )
+                        .with_text_alignment(TextAlignment::Center)
+                        .with_style(Style {
+                            width: Val::Px(120.),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        }),
+                        SettingsLabel::Field(field),
+                    ));
+                    spawn_button(row, SettingsButton::Step(field, 1), 32., ">", None);
+                });
+            }
+
+            for action in Action::ALL {
+                spawn_row(screen, action.label(), |row| {
+                    let label = SettingsLabel::Binding(action);
+                    spawn_button(
+                        row,
+                        SettingsButton::Rebind(action),
+                        200.,
+                        label.text(settings, None),
+                        Some(label),
+                    );
+                });
+            }
+
+            screen
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(12.),
+                        margin: UiRect::top(Val::Px(8.)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|buttons| {
+                    spawn_button(buttons, SettingsButton::Reset, 160., "Reset", None);
+                    spawn_button(buttons, SettingsButton::Back, 160., "Back", None);
+                });
+        });
+}
+
+pub fn open_settings_menu(
+    mut commands: Commands,
+    mut events: EventReader<OpenSettingsEvent>,
+    screens: Query<(), With<SettingsScreen>>,
+    settings: Res<Settings>,
+) {
+    if events.iter().count() > 0 && screens.is_empty() {
+        spawn_settings_screen(&mut commands, &settings);
+    }
+}
+
+fn close_settings_menu(commands: &mut Commands, screens: &Query<Entity, With<SettingsScreen>>) {
+    for screen in screens.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+    commands.remove_resource::<Rebinding>();
+}
+
+pub fn press_settings_buttons(
+    mut commands: Commands,
+    mut buttons: Query<(&Interaction, &SettingsButton, &mut BackgroundColor), Changed<Interaction>>,
+    screens: Query<Entity, With<SettingsScreen>>,
+    mut settings: ResMut<Settings>,
+    rebinding: Option<Res<Rebinding>>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+) {
+    let pause = settings.controls.get(Action::Pause);
+    if rebinding.is_none() && pause.just_pressed(&keys, &mouse) {
+        close_settings_menu(&mut commands, &screens);
+        return;
+    }
+
+    for (interaction, button, mut background) in buttons.iter_mut() {
+        *background = match interaction {
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            _ => BUTTON_COLOR,
+        }
+        .into();
+
+        // The click that finished a rebinding must not press another button.
+        if *interaction != Interaction::Pressed || rebinding.is_some() {
+            continue;
+        }
+
+        match *button {
+            SettingsButton::Step(field, steps) => field.step(&mut settings, steps),
+            SettingsButton::Rebind(action) => commands.insert_resource(Rebinding::new(action)),
+            SettingsButton::Reset => *settings = Settings::default(),
+            SettingsButton::Back => close_settings_menu(&mut commands, &screens),
+        }
+    }
+}
+
+/// Binds the action waiting for input to the next key or mouse button
+/// pressed. The pause binding cancels, unless it is pressed a second time
+/// to bind it.
+pub fn capture_rebinding(
+    mut commands: Commands,
+    mut settings: ResMut<Settings>,
+    mut rebinding: Option<ResMut<Rebinding>>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+) {
+    let Some(rebinding) = rebinding.as_deref_mut() else {
+        return;
+    };
+
+    let pressed = keys
+        .get_just_pressed()
+        .next()
+        .map(|key| Binding::Key(*key))
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Mouse(*button))
+        });
+
+    let Some(pressed) = pressed else {
+        return;
+    };
+
+    let is_pause = pressed == settings.controls.get(Action::Pause);
+    match (is_pause, rebinding.confirming) {
+        (true, false) => rebinding.confirming = true,
+        (false, true) => commands.remove_resource::<Rebinding>(),
+        _ => {
+            settings.controls.set(rebinding.action, pressed);
+            commands.remove_resource::<Rebinding>();
+        }
+    }
+}
+
+pub fn update_settings_labels(
+    mut labels: Query<(&SettingsLabel, &mut Text)>,
+    settings: Res<Settings>,
+    rebinding: Option<Res<Rebinding>>,
+) {
+    for (label, mut text) in labels.iter_mut() {
+        let value = label.text(&settings, rebinding.as_deref());
+        // Only touches texts that changed, so the UI is not laid out again every frame.
+        if text
+            .sections
+            .first()
+            .is_some_and(|section| section.value != value)
+        {
+            text.sections[0].value = value;
+        }
+    }
+}
@@ -0,0 +1,174 @@
+mod controls;
+mod menu;
+
+use bevy::{
+    prelude::*,
+    window::{PresentMode, PrimaryWindow},
+};
+use serde::{Deserialize, Serialize};
+
+pub use controls::*;
+pub use menu::*;
+
+use crate::{player::CameraOptions, save::SaveStorage, sound::SoundOptions};
+
+const SETTINGS_KEY: &str = "settings.json";
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let storage = app
+            .world
+            .get_resource::<SaveStorage>()
+            .cloned()
+            .unwrap_or_default();
+
+        app.insert_resource(Settings::load(&storage))
+            .add_event::<OpenSettingsEvent>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        apply_audio_settings,
+                        apply_video_settings,
+                        apply_camera_settings,
+                    )
+                        .run_if(resource_changed::<Settings>()),
+                    persist_settings,
+                    open_settings_menu,
+                    capture_rebinding.before(press_settings_buttons),
+                    press_settings_buttons,
+                    update_settings_labels.after(press_settings_buttons),
+                ),
+            );
+    }
+}
+
+/// Player preferences, kept across sessions. Missing fields fall back to
+/// their defaults so older settings files keep loading.
+#[derive(Debug, Clone, Default, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub audio: AudioSettings,
+    pub video: VideoSettings,
+    pub camera: CameraSettings,
+    pub controls: KeyBindings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        let sound = SoundOptions::default();
+        Self {
+            master_volume: sound.master_volume,
+            music_volume: sound.music_volume,
+            sfx_volume: sound.sfx_volume,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VideoSettings {
+    pub present_mode: PresentMode,
+    /// Overrides the scale factor the OS reports for the window.
+    pub window_scale: Option<f32>,
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        Self {
+            present_mode: PresentMode::AutoNoVsync,
+            window_scale: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraSettings {
+    pub follow_speed: f32,
+    pub character_to_cursor_center: f32,
+    pub character_bob_intensity: f32,
+    pub screen_shake: bool,
+    pub zoom: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        let camera = CameraOptions::default();
+        Self {
+            follow_speed: camera.follow_speed,
+            character_to_cursor_center: camera.character_to_cursor_center,
+            character_bob_intensity: camera.character_bob_intensity,
+            screen_shake: camera.screen_shake,
+            zoom: camera.zoom,
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the stored settings, falling back to the defaults when there
+    /// are none or they cannot be parsed.
+    pub fn load(storage: &SaveStorage) -> Self {
+        match storage.read(SETTINGS_KEY) {
+            Ok(Some(text)) => serde_json::from_str(&text).unwrap_or_else(|err| {
+                warn!("Ignoring malformed settings: {err}");
+                Settings::default()
+            }),
+            Ok(None) => Settings::default(),
+            Err(err) => {
+                warn!("Could not read settings: {err}");
+                Settings::default()
+            }
+        }
+    }
+}
+
+/// Writes the settings back to storage whenever they change.
+pub fn persist_settings(settings: Res<Settings>, storage: Res<SaveStorage>) {
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+
+    let written = serde_json::to_string_pretty(settings.as_ref())
+        .map_err(Into::into)
+        .and_then(|text| storage.write(SETTINGS_KEY, &text));
+    if let Err(err) = written {
+        error!("Could not save settings: {err}");
+    }
+}
+
+pub fn apply_audio_settings(settings: Res<Settings>, mut sound: ResMut<SoundOptions>) {
+    sound.master_volume = settings.audio.master_volume;
+    sound.music_volume = settings.audio.music_volume;
+    sound.sfx_volume = settings.audio.sfx_volume;
+}
+
+pub fn apply_video_settings(
+    settings: Res<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    for mut window in windows.iter_mut() {
+        window.present_mode = settings.video.present_mode;
+        window
+            .resolution
+            .set_scale_factor_override(settings.video.window_scale.map(f64::from));
+    }
+}
+
+pub fn apply_camera_settings(settings: Res<Settings>, mut camera: ResMut<CameraOptions>) {
+    camera.follow_speed = settings.camera.follow_speed;
+    camera.character_to_cursor_center = settings.camera.character_to_cursor_center;
+    camera.character_bob_intensity = settings.camera.character_bob_intensity;
+    camera.screen_shake = settings.camera.screen_shake;
+    camera.zoom = settings.camera.zoom;
+}
@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect, Deserialize)]
+pub enum SoundChannel {
+    #[default]
+    Sfx,
+    Music,
+}
+
+fn one() -> f32 {
+    1.
+}
+
+fn default_max_voices() -> usize {
+    4
+}
+
+fn default_spatial() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SoundDef {
+    /// One of these is picked at random every time the sound plays.
+    pub clips: Vec<String>,
+    #[serde(default = "one")]
+    pub volume: f32,
+    /// Volume is scaled by up to this fraction either way.
+    #[serde(default)]
+    pub volume_variation: f32,
+    /// Pitch is scaled by up to this fraction either way.
+    #[serde(default)]
+    pub pitch_variation: f32,
+    /// Most copies of the sound playing at once, the oldest is cut off past
+    /// this. Zero mutes the sound.
+    #[serde(default = "default_max_voices")]
+    pub max_voices: usize,
+    /// Attenuated and panned by its position relative to the listener.
+    #[serde(default = "default_spatial")]
+    pub spatial: bool,
+    #[serde(default)]
+    pub looping: bool,
+    #[serde(default)]
+    pub channel: SoundChannel,
+}
+
+#[derive(Debug, Clone)]
+pub struct Sound {
+    pub def: SoundDef,
+    pub clips: Vec<Handle<AudioSource>>,
+}
+
+impl Sound {
+    pub fn random_clip(&self) -> Option<Handle<AudioSource>> {
+        (!self.clips.is_empty()).then(|| self.clips[fastrand::usize(..self.clips.len())].clone())
+    }
+}
+
+/// Maps sound names used by gameplay to clips and how to play them,
+/// authored in a `.sounds.ron` file.
+#[derive(Debug, TypeUuid, TypePath)]
+#[uuid = "6e1f4b92-3d7a-4c85-b0e6-9a2c51d8f347"]
+pub struct SoundBank {
+    pub sounds: HashMap<String, Sound>,
+}
+
+#[derive(Deserialize)]
+struct SoundBankDef {
+    sounds: HashMap<String, SoundDef>,
+}
+
+#[derive(Default)]
+pub struct SoundBankLoader;
+
+impl AssetLoader for SoundBankLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let def = ron::de::from_bytes::<SoundBankDef>(bytes)?;
+
+            let mut dependencies = vec![];
+            let sounds = def
+                .sounds
+                .into_iter()
+                .map(|(name, def)| {
+                    let clips = def
+                        .clips
+                        .iter()
+                        .map(|clip| {
+                            let path = AssetPath::new(PathBuf::from(clip), None);
+                            dependencies.push(path.clone());
+                            load_context.get_handle(path)
+                        })
+                        .collect();
+                    (name, Sound { def, clips })
+                })
+                .collect();
+
+            load_context.set_default_asset(
+                LoadedAsset::new(SoundBank { sounds }).with_dependencies(dependencies),
+            );
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["sounds.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct SoundLibrary {
+    pub bank: Handle<SoundBank>,
+}
+
+impl FromWorld for SoundLibrary {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            bank: world.resource::<AssetServer>().load("default.sounds.ron"),
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct Sounds<'w> {
+    library: Res<'w, SoundLibrary>,
+    banks: Res<'w, Assets<SoundBank>>,
+}
+
+impl<'w> Sounds<'w> {
+    pub fn get(&self, name: &str) -> Option<&Sound> {
+        self.banks
+            .get(&self.library.bank)
+            .and_then(|bank| bank.sounds.get(name))
+    }
+}
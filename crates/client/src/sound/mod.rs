@@ -0,0 +1,287 @@
+mod bank;
+mod triggers;
+
+use std::borrow::Cow;
+
+use bevy::{
+    audio::{PlaybackMode, Volume, VolumeLevel},
+    prelude::*,
+};
+use bevy_inspector_egui::{prelude::ReflectInspectorOptions, InspectorOptions};
+
+pub use bank::*;
+pub use triggers::*;
+
+use crate::core::GameplaySet;
+
+/// Distance between the virtual ears. Emitters are kept between them, so
+/// they only pan and the audio backend never attenuates them on its own.
+const EAR_GAP: f32 = 0.2;
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SoundOptions>()
+            .init_resource::<SoundOptions>()
+            .add_asset::<SoundBank>()
+            .init_asset_loader::<SoundBankLoader>()
+            .init_resource::<SoundLibrary>()
+            .add_event::<PlaySoundEvent>()
+            .add_systems(
+                Update,
+                (
+                    (attack_sounds, damage_sounds, footstep_sounds)
+                        .in_set(GameplaySet)
+                        .before(play_sounds),
+                    // Gameplay halts while the upgrade is picked, the chime should not.
+                    level_up_sounds.before(play_sounds),
+                    play_sounds,
+                    apply_sound_volumes,
+                ),
+            );
+    }
+}
+
+#[derive(Resource, Reflect, InspectorOptions)]
+#[reflect(Resource, InspectorOptions)]
+pub struct SoundOptions {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    /// Pixels from the listener at which spatial sounds fade out completely.
+    pub max_distance: f32,
+    /// Pixels to the side of the listener at which sounds are fully panned.
+    pub pan_distance: f32,
+    /// Pixels a hero walks between footsteps.
+    pub footstep_stride: f32,
+}
+
+impl Default for SoundOptions {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.,
+            music_volume: 0.6,
+            sfx_volume: 1.,
+            max_distance: 200.,
+            pan_distance: 120.,
+            footstep_stride: 12.,
+        }
+    }
+}
+
+impl SoundOptions {
+    pub fn channel_volume(&self, channel: SoundChannel) -> f32 {
+        let channel = match channel {
+            SoundChannel::Sfx => self.sfx_volume,
+            SoundChannel::Music => self.music_volume,
+        };
+        (self.master_volume * channel).max(0.)
+    }
+}
+
+/// Where spatial sounds are heard from, usually the camera.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct AudioListener;
+
+/// Asks for a sound from the bank, at a world position for spatial sounds.
+#[derive(Debug, Clone, Event)]
+pub struct PlaySoundEvent {
+    pub name: Cow<'static, str>,
+    pub position: Option<Vec2>,
+}
+
+impl PlaySoundEvent {
+    pub fn at(name: impl Into<Cow<'static, str>>, position: Vec2) -> Self {
+        Self {
+            name: name.into(),
+            position: Some(position),
+        }
+    }
+}
+
+/// A playing sound, tracked for voice limiting and volume changes.
+#[derive(Debug, Clone, Component)]
+pub struct SoundVoice {
+    pub name: Cow<'static, str>,
+    pub channel: SoundChannel,
+    /// Volume before channel and master volume are applied.
+    pub volume: f32,
+    pub started_at: f32,
+}
+
+fn vary(value: f32, variation: f32) -> f32 {
+    value * (1. + (fastrand::f32() * 2. - 1.) * variation)
+}
+
+/// Playing voices, with the time they started, to stop so another copy of
+/// the sound fits in `max_voices`. The oldest go first. `None` when the
+/// sound has no voices and must not play at all.
+fn voices_to_steal(mut playing: Vec<(Entity, f32)>, max_voices: usize) -> Option<Vec<Entity>> {
+    if max_voices == 0 {
+        return None;
+    }
+
+    playing.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+    let excess = (playing.len() + 1).saturating_sub(max_voices);
+    Some(
+        playing
+            .into_iter()
+            .take(excess)
+            .map(|(entity, _)| entity)
+            .collect(),
+    )
+}
+
+pub fn play_sounds(
+    mut commands: Commands,
+    mut events: EventReader<PlaySoundEvent>,
+    voices: Query<(Entity, &SoundVoice)>,
+    listener: Query<&GlobalTransform, With<AudioListener>>,
+    sounds: Sounds,
+    time: Res<Time>,
+    options: Res<SoundOptions>,
+) {
+    let listener = listener
+        .get_single()
+        .map_or(Vec2::ZERO, |transform| transform.translation().truncate());
+    let now = time.raw_elapsed_seconds();
+    let mut stopped = vec![];
+
+    for event in events.iter() {
+        let Some(sound) = sounds.get(&event.name) else {
+            continue;
+        };
+        let Some(clip) = sound.random_clip() else {
+            continue;
+        };
+        let def = &sound.def;
+
+        let offset = event
+            .position
+            .filter(|_| def.spatial)
+            .map(|position| position - listener);
+        let attenuation = offset.map_or(1., |offset| {
+            (1. - offset.length() / options.max_distance.max(f32::EPSILON))
+                .clamp(0., 1.)
+                .powi(2)
+        });
+        if attenuation <= 0. {
+            continue;
+        }
+
+        let playing = voices
+            .iter()
+            .filter(|(entity, voice)| voice.name == event.name && !stopped.contains(entity))
+            .map(|(entity, voice)| (entity, voice.started_at))
+            .collect();
+        let Some(stolen) = voices_to_steal(playing, def.max_voices) else {
+            continue;
+        };
+        for entity in stolen {
+            commands.entity(entity).despawn();
+            stopped.push(entity);
+        }
+
+        let voice = SoundVoice {
+            name: event.name.clone(),
+            channel: def.channel,
+            volume: vary(def.volume, def.volume_variation) * attenuation,
+            started_at: now,
+        };
+        let mode = if def.looping {
+            PlaybackMode::Loop
+        } else {
+            PlaybackMode::Despawn
+        };
+        let settings = PlaybackSettings {
+            mode,
+            volume: Volume::Relative(VolumeLevel::new(
+                voice.volume * options.channel_volume(def.channel),
+            )),
+            speed: vary(1., def.pitch_variation).max(0.01),
+            paused: false,
+        };
+
+        match offset {
+            Some(offset) => {
+                let pan = (offset.x / options.pan_distance.max(f32::EPSILON)).clamp(-1., 1.);
+                commands.spawn((
+                    Name::new("Sound"),
+                    SpatialAudioBundle {
+                        source: clip,
+                        settings,
+                        spatial: SpatialSettings::new(
+                            Transform::IDENTITY,
+                            EAR_GAP,
+                            Vec3::X * pan * EAR_GAP / 2.,
+                        ),
+                    },
+                    voice,
+                ));
+            }
+            None => {
+                commands.spawn((
+                    Name::new("Sound"),
+                    AudioBundle {
+                        source: clip,
+                        settings,
+                    },
+                    voice,
+                ));
+            }
+        }
+    }
+}
+
+/// Applies changes to the master and channel volumes to playing sounds.
+pub fn apply_sound_volumes(
+    voices: Query<(&SoundVoice, Option<&AudioSink>, Option<&SpatialAudioSink>)>,
+    options: Res<SoundOptions>,
+) {
+    if !options.is_changed() {
+        return;
+    }
+
+    for (voice, sink, spatial_sink) in voices.iter() {
+        let volume = voice.volume * options.channel_volume(voice.channel);
+        if let Some(sink) = sink {
+            sink.set_volume(volume);
+        }
+        if let Some(sink) = spatial_sink {
+            sink.set_volume(volume);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oldest_voices_are_stolen() {
+        let voice = |index, started_at| (Entity::from_raw(index), started_at);
+        let playing = vec![voice(0, 2.), voice(1, 0.5), voice(2, 1.)];
+
+        assert_eq!(voices_to_steal(playing.clone(), 4), Some(vec![]));
+        assert_eq!(
+            voices_to_steal(playing.clone(), 3),
+            Some(vec![Entity::from_raw(1)])
+        );
+        assert_eq!(
+            voices_to_steal(playing.clone(), 1),
+            Some(vec![
+                Entity::from_raw(1),
+                Entity::from_raw(2),
+                Entity::from_raw(0)
+            ])
+        );
+        assert_eq!(voices_to_steal(vec![], 1), Some(vec![]));
+    }
+
+    #[test]
+    fn sounds_without_voices_never_play() {
+        assert_eq!(voices_to_steal(vec![], 0), None);
+        assert_eq!(voices_to_steal(vec![(Entity::from_raw(0), 0.)], 0), None);
+    }
+}
@@ -0,0 +1,88 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{PlaySoundEvent, SoundOptions};
+use crate::{
+    core::DamageTakenEvent,
+    player::{PlayerAttackEvent, PlayerMarker, PlayerMotor},
+    progression::LevelUpEvent,
+};
+
+pub fn attack_sounds(
+    mut events: EventReader<PlayerAttackEvent>,
+    mut sounds: EventWriter<PlaySoundEvent>,
+) {
+    for event in events.iter() {
+        sounds.send(PlaySoundEvent::at("attack", event.player_pos));
+    }
+}
+
+pub fn damage_sounds(
+    mut events: EventReader<DamageTakenEvent>,
+    mut sounds: EventWriter<PlaySoundEvent>,
+    targets: Query<&GlobalTransform>,
+    heroes: Query<(), With<PlayerMarker>>,
+) {
+    for event in events.iter() {
+        let position = targets
+            .get(event.taken_by)
+            .map_or(event.from_position, |target| {
+                target.translation().truncate()
+            });
+
+        let name = if heroes.contains(event.taken_by) {
+            "hero_hurt"
+        } else {
+            "hit"
+        };
+        sounds.send(PlaySoundEvent::at(name, position));
+
+        if event.killing_blow {
+            sounds.send(PlaySoundEvent::at("death", position));
+        }
+    }
+}
+
+pub fn level_up_sounds(
+    mut events: EventReader<LevelUpEvent>,
+    mut sounds: EventWriter<PlaySoundEvent>,
+    heroes: Query<&GlobalTransform>,
+) {
+    for event in events.iter() {
+        if let Ok(transform) = heroes.get(event.entity) {
+            sounds.send(PlaySoundEvent::at(
+                "level_up",
+                transform.translation().truncate(),
+            ));
+        }
+    }
+}
+
+/// Plays a footstep every stride a hero walks.
+pub fn footstep_sounds(
+    mut walked: Local<HashMap<Entity, f32>>,
+    mut sounds: EventWriter<PlaySoundEvent>,
+    heroes: Query<(Entity, &PlayerMotor, &GlobalTransform)>,
+    time: Res<Time>,
+    options: Res<SoundOptions>,
+) {
+    walked.retain(|entity, _| heroes.contains(*entity));
+
+    for (entity, motor, transform) in heroes.iter() {
+        let distance = walked.entry(entity).or_default();
+        let speed = motor.velocity.length();
+        if speed < f32::EPSILON {
+            // Start the next walk with a step right away.
+            *distance = options.footstep_stride;
+            continue;
+        }
+
+        *distance += speed * time.delta_seconds();
+        if *distance >= options.footstep_stride {
+            *distance = 0.;
+            sounds.send(PlaySoundEvent::at(
+                "footstep",
+                transform.translation().truncate(),
+            ));
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+
+use super::{begin_new_run, GameState};
+use crate::{
+    level::DungeonRun,
+    save::{LoadGameEvent, SaveGameEvent},
+    settings::{OpenSettingsEvent, SettingsScreen},
+};
+
+pub const TITLE_COLOR: Color = Color::rgb(200. / 255., 172. / 255., 147. / 255.);
+pub const TEXT_COLOR: Color = Color::rgb(177. / 255., 116. / 255., 61. / 255.);
+pub const BUTTON_COLOR: Color = Color::rgb(45. / 255., 41. / 255., 28. / 255.);
+pub const BUTTON_HOVER_COLOR: Color = Color::rgb(69. / 255., 62. / 255., 42. / 255.);
+
+/// Root of a full-screen menu, despawned when its state is left.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct MenuScreen;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum MenuButton {
+    NewRun,
+    Resume,
+    Restart,
+    Save,
+    Load,
+    Settings,
+    MainMenu,
+}
+
+impl MenuButton {
+    fn label(&self) -> &'static str {
+        match self {
+            MenuButton::NewRun => "New Run",
+            MenuButton::Resume => "Resume",
+            MenuButton::Restart => "Restart",
+            MenuButton::Save => "Save",
+            MenuButton::Load => "Load",
+            MenuButton::Settings => "Settings",
+            MenuButton::MainMenu => "Main Menu",
+        }
+    }
+}
+
+fn spawn_menu_screen(
+    commands: &mut Commands,
+    name: &'static str,
+    title: &str,
+    subtitle: Option<String>,
+    buttons: &[MenuButton],
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.),
+                    ..default()
+                },
+                background_color: Color::rgba_u8(0x0A, 0x0D, 0x11, 0xC0).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            MenuScreen,
+            Name::new(name),
+        ))
+        .with_children(|screen| {
+            screen.spawn(TextBundle::from_section(
+                title,
+                TextStyle {
+                    font_size: 48.,
+                    color: TITLE_COLOR,
+                    ..default()
+                },
+            ));
+
+            if let Some(subtitle) = subtitle {
+                screen.spawn(TextBundle::from_section(
+                    subtitle,
+                    TextStyle {
+                        font_size: 20.,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                ));
+            }
+
+            for &button in buttons {
+                screen
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(240.),
+                                justify_content: JustifyContent::Center,
+                                padding: UiRect::all(Val::Px(8.)),
+                                ..default()
+                            },
+                            background_color: BUTTON_COLOR.into(),
+                            ..default()
+                        },
+                        button,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            button.label(),
+                            TextStyle {
+                                font_size: 24.,
+                                color: TITLE_COLOR,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+pub fn spawn_main_menu(mut commands: Commands) {
+    spawn_menu_screen(
+        &mut commands,
+        "Main Menu",
+        "Magum",
+        Some("Press Enter to start".into()),
+        &[MenuButton::NewRun, MenuButton::Load, MenuButton::Settings],
+    );
+}
+
+pub fn spawn_pause_menu(mut commands: Commands) {
+    spawn_menu_screen(
+        &mut commands,
+        "Pause Menu",
+        "Paused",
+        None,
+        &[
+            MenuButton::Resume,
+            MenuButton::Save,
+            MenuButton::Load,
+            MenuButton::Settings,
+            MenuButton::Restart,
+            MenuButton::MainMenu,
+        ],
+    );
+}
+
+pub fn spawn_game_over_screen(mut commands: Commands, run: Res<DungeonRun>) {
+    spawn_menu_screen(
+        &mut commands,
+        "Game Over Screen",
+        "Game Over",
+        Some(format!("Reached floor {}", run.floor)),
+        &[MenuButton::Restart, MenuButton::Load, MenuButton::MainMenu],
+    );
+}
+
+pub fn despawn_menu_screens(mut commands: Commands, screens: Query<Entity, With<MenuScreen>>) {
+    for screen in screens.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+}
+
+pub fn press_menu_buttons(
+    mut commands: Commands,
+    mut buttons: Query<(&Interaction, &MenuButton, &mut BackgroundColor), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut save: EventWriter<SaveGameEvent>,
+    mut load: EventWriter<LoadGameEvent>,
+    mut open_settings: EventWriter<OpenSettingsEvent>,
+) {
+    for (interaction, button, mut background) in buttons.iter_mut() {
+        *background = match interaction {
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            _ => BUTTON_COLOR,
+        }
+        .into();
+
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            MenuButton::NewRun | MenuButton::Restart => {
+                begin_new_run(&mut commands, &mut next_state)
+            }
+            MenuButton::Resume => next_state.set(GameState::Playing),
+            MenuButton::Save => save.send(SaveGameEvent),
+            MenuButton::Load => load.send(LoadGameEvent),
+            MenuButton::Settings => open_settings.send(OpenSettingsEvent),
+            MenuButton::MainMenu => next_state.set(GameState::MainMenu),
+        }
+    }
+}
+
+/// Starts a run from the main and game over screens with Enter.
+pub fn menu_shortcuts(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    settings_screens: Query<(), With<SettingsScreen>>,
+) {
+    if !keys.just_pressed(KeyCode::Return) || !settings_screens.is_empty() {
+        return;
+    }
+
+    if matches!(state.get(), GameState::MainMenu | GameState::GameOver) {
+        begin_new_run(&mut commands, &mut next_state);
+    }
+}
@@ -0,0 +1,171 @@
+mod menus;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::RapierConfiguration;
+
+pub use menus::*;
+
+use crate::{
+    core::{damage_system, GameplaySet, HealthChangedEvent},
+    fx::{DamageNumber, DamageNumberPool},
+    hero::spawn_hero,
+    level::{CurrentLevel, DungeonRun, LevelEntity},
+    particles::{Particle, ParticleEmitter},
+    player::PlayerMarker,
+    progression::{LevelUpScreen, PendingLevelUp, ProgressionRng},
+    settings::{Action, Controls, SettingsScreen},
+    sound::SoundVoice,
+    AnimationSet,
+};
+
+pub struct GameStatePlugin;
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<GameState>()
+            .configure_set(Update, GameplaySet.run_if(in_state(GameState::Playing)))
+            .configure_set(
+                PostUpdate,
+                AnimationSet::Animate.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnEnter(GameState::MainMenu), (despawn_run, spawn_main_menu))
+            .add_systems(OnExit(GameState::MainMenu), despawn_menu_screens)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (
+                    (despawn_run, start_run)
+                        .chain()
+                        .run_if(resource_exists::<StartRun>()),
+                    resume_world,
+                ),
+            )
+            .add_systems(OnExit(GameState::Playing), halt_world)
+            .add_systems(OnEnter(GameState::Paused), spawn_pause_menu)
+            .add_systems(OnExit(GameState::Paused), despawn_menu_screens)
+            .add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen)
+            .add_systems(OnExit(GameState::GameOver), despawn_menu_screens)
+            .add_systems(
+                Update,
+                (
+                    detect_game_over.after(damage_system).in_set(GameplaySet),
+                    toggle_pause,
+                    press_menu_buttons,
+                    menu_shortcuts,
+                ),
+            );
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, States)]
+pub enum GameState {
+    #[default]
+    MainMenu,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+/// Present while a fresh run should be set up the next time the game
+/// enters [`GameState::Playing`].
+#[derive(Debug, Default, Resource)]
+pub struct StartRun;
+
+/// Leaves the current run behind and starts over.
+pub fn begin_new_run(commands: &mut Commands, next_state: &mut NextState<GameState>) {
+    commands.init_resource::<StartRun>();
+    next_state.set(GameState::Playing);
+}
+
+type RunEntityFilter = (
+    Or<(
+        With<PlayerMarker>,
+        With<LevelEntity>,
+        With<DamageNumber>,
+        With<Particle>,
+        With<ParticleEmitter>,
+        With<SoundVoice>,
+        With<LevelUpScreen>,
+    )>,
+    Without<Parent>,
+);
+
+/// Despawns everything belonging to the current run, leaving the camera
+/// and the UI in place.
+pub fn despawn_run(
+    mut commands: Commands,
+    run_entities: Query<Entity, RunEntityFilter>,
+    mut damage_numbers: ResMut<DamageNumberPool>,
+) {
+    for entity in run_entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    damage_numbers.spawned = 0;
+    commands.remove_resource::<PendingLevelUp>();
+}
+
+pub fn start_run(
+    mut commands: Commands,
+    mut run: ResMut<DungeonRun>,
+    mut rng: ResMut<ProgressionRng>,
+    mut current: ResMut<CurrentLevel>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.remove_resource::<StartRun>();
+
+    *run = DungeonRun::default();
+    *rng = ProgressionRng::for_run(&run);
+    *current = CurrentLevel::start(&asset_server);
+    spawn_hero(&mut commands);
+}
+
+/// Lets time and physics run again, unless a level-up choice still waits.
+pub fn resume_world(
+    mut time: ResMut<Time>,
+    mut physics: ResMut<RapierConfiguration>,
+    pending: Option<Res<PendingLevelUp>>,
+    starting: Option<Res<StartRun>>,
+) {
+    if pending.is_some() && starting.is_none() {
+        return;
+    }
+
+    time.unpause();
+    physics.physics_pipeline_active = true;
+}
+
+pub fn halt_world(mut time: ResMut<Time>, mut physics: ResMut<RapierConfiguration>) {
+    time.pause();
+    physics.physics_pipeline_active = false;
+}
+
+pub fn detect_game_over(
+    mut events: EventReader<HealthChangedEvent>,
+    heroes: Query<(), With<PlayerMarker>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if events
+        .iter()
+        .any(|event| event.current_hp == 0 && heroes.contains(event.entity))
+    {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+pub fn toggle_pause(
+    controls: Controls,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    settings_screens: Query<(), With<SettingsScreen>>,
+) {
+    // The settings menu closes on its own first.
+    if !controls.just_pressed(Action::Pause) || !settings_screens.is_empty() {
+        return;
+    }
+
+    match state.get() {
+        GameState::Playing => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::Playing),
+        _ => {}
+    }
+}
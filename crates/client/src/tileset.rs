@@ -1,48 +1,327 @@
-use bevy::prelude::*;
-use lazy_static::lazy_static;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    sprite::Anchor,
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
 
-lazy_static! {
-    pub static ref CHECKERBOARD: TextureAtlasSprite = TextureAtlasSprite {
-        index: 0,
-        ..default()
-    };
-    pub static ref WALL: TextureAtlasSprite = TextureAtlasSprite {
-        color: Color::rgb_u8(0x64, 0x6C, 0x5E),
-        index: 1,
-        ..default()
-    };
-    pub static ref CHEST: TextureAtlasSprite = TextureAtlasSprite {
-        index: 2,
-        ..default()
-    };
-    pub static ref ROCK: TextureAtlasSprite = TextureAtlasSprite {
-        color: Color::rgb_u8(0x64, 0x6C, 0x5E),
-        index: 3,
-        ..default()
-    };
-    pub static ref DUMMY: TextureAtlasSprite = TextureAtlasSprite {
-        color: Color::rgb_u8(0x7D, 0x5C, 0x51),
-        index: 17,
-        ..default()
-    };
-    pub static ref DUMMY_BROKEN: TextureAtlasSprite = TextureAtlasSprite {
-        color: Color::rgb_u8(0x7D, 0x5C, 0x51) * 0.7,
-        index: 18,
-        ..default()
-    };
-    pub static ref GRASS: TextureAtlasSprite = TextureAtlasSprite {
-        color: Color::rgb_u8(0x48, 0x4A, 0x16),
-        index: 4,
-        ..default()
-    };
-    pub static ref PLAYER: TextureAtlasSprite = TextureAtlasSprite {
-        color: Color::rgb_u8(0xFF, 0xFF, 0xFF),
-        index: 16,
-        ..default()
-    };
-    pub static ref SWORD: TextureAtlasSprite = TextureAtlasSprite {
-        color: Color::rgb_u8(0x91, 0x87, 0x83),
-        index: 13,
-        ..default()
+use crate::{level::TileKind, PaletteColor, Palettes};
+
+pub const TILE_SIZE: f32 = 8.;
+pub const SPRITE_CATALOG_PATH: &str = "tileset.sprites.ron";
+
+pub struct TilesetPlugin;
+
+impl Plugin for TilesetPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SpriteName>()
+            .add_asset::<SpriteCatalog>()
+            .init_asset_loader::<SpriteCatalogLoader>()
+            .init_resource::<SpriteLibrary>()
+            .add_systems(
+                Update,
+                (
+                    build_sprite_atlases,
+                    apply_sprite_names.after(build_sprite_atlases),
+                ),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AtlasDef {
+    pub texture: String,
+    pub tile_size: (f32, f32),
+    pub columns: usize,
+    pub rows: usize,
+    #[serde(default)]
+    pub padding: Option<(f32, f32)>,
+    #[serde(default)]
+    pub offset: Option<(f32, f32)>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+pub enum SpriteAnchor {
+    #[default]
+    Center,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    CenterLeft,
+    CenterRight,
+    TopLeft,
+    TopCenter,
+    TopRight,
+    Custom(f32, f32),
+}
+
+impl From<SpriteAnchor> for Anchor {
+    fn from(anchor: SpriteAnchor) -> Self {
+        match anchor {
+            SpriteAnchor::Center => Anchor::Center,
+            SpriteAnchor::BottomLeft => Anchor::BottomLeft,
+            SpriteAnchor::BottomCenter => Anchor::BottomCenter,
+            SpriteAnchor::BottomRight => Anchor::BottomRight,
+            SpriteAnchor::CenterLeft => Anchor::CenterLeft,
+            SpriteAnchor::CenterRight => Anchor::CenterRight,
+            SpriteAnchor::TopLeft => Anchor::TopLeft,
+            SpriteAnchor::TopCenter => Anchor::TopCenter,
+            SpriteAnchor::TopRight => Anchor::TopRight,
+            SpriteAnchor::Custom(x, y) => Anchor::Custom(Vec2::new(x, y)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpriteDef {
+    pub atlas: String,
+    pub index: usize,
+    /// Slot of the active palette the sprite is tinted with, white when omitted.
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default = "default_shade")]
+    pub shade: f32,
+    #[serde(default)]
+    pub flip_x: bool,
+    #[serde(default)]
+    pub flip_y: bool,
+    #[serde(default)]
+    pub anchor: SpriteAnchor,
+    /// Level tile the sprite stands for when painted in a map editor.
+    #[serde(default)]
+    pub kind: Option<TileKind>,
+}
+
+fn default_shade() -> f32 {
+    1.
+}
+
+impl SpriteDef {
+    pub fn palette_color(&self) -> Option<PaletteColor> {
+        self.color
+            .as_ref()
+            .map(|slot| PaletteColor::new(slot, self.shade))
+    }
+
+    pub fn to_sprite(&self, palettes: &Palettes) -> TextureAtlasSprite {
+        TextureAtlasSprite {
+            index: self.index,
+            color: self
+                .palette_color()
+                .map(|color| palettes.resolve(&color))
+                .unwrap_or(Color::WHITE),
+            flip_x: self.flip_x,
+            flip_y: self.flip_y,
+            anchor: self.anchor.into(),
+            ..default()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, TypeUuid, TypePath)]
+#[uuid = "3b9d2c71-8e4a-4f06-b5d2-7a1c9e0f4b38"]
+pub struct SpriteCatalog {
+    pub atlases: HashMap<String, AtlasDef>,
+    pub sprites: HashMap<String, SpriteDef>,
+}
+
+impl SpriteCatalog {
+    /// Tile kinds of the sprites in `atlas`, keyed by their index.
+    pub fn tile_kinds(&self, atlas: &str) -> HashMap<usize, TileKind> {
+        self.sprites
+            .values()
+            .filter(|sprite| sprite.atlas == atlas)
+            .filter_map(|sprite| Some((sprite.index, sprite.kind?)))
+            .collect()
+    }
+}
+
+#[derive(Default)]
+pub struct SpriteCatalogLoader;
+
+impl AssetLoader for SpriteCatalogLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let catalog = ron::de::from_bytes::<SpriteCatalog>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(catalog));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["sprites.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct SpriteLibrary {
+    pub catalog: Handle<SpriteCatalog>,
+    pub atlases: HashMap<String, Handle<TextureAtlas>>,
+}
+
+impl FromWorld for SpriteLibrary {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            catalog: world.resource::<AssetServer>().load(SPRITE_CATALOG_PATH),
+            atlases: HashMap::new(),
+        }
+    }
+}
+
+/// Names the catalog entry an entity's [`TextureAtlasSprite`] comes from.
+/// The sprite and atlas are filled in once the catalog is loaded and
+/// refreshed whenever it changes.
+#[derive(Debug, Clone, PartialEq, Eq, Component, Reflect)]
+#[reflect(Component)]
+pub struct SpriteName(pub String);
+
+impl SpriteName {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+}
+
+impl Default for SpriteName {
+    fn default() -> Self {
+        Self::new("checkerboard")
+    }
+}
+
+#[derive(SystemParam)]
+pub struct Sprites<'w> {
+    library: Res<'w, SpriteLibrary>,
+    catalogs: Res<'w, Assets<SpriteCatalog>>,
+    palettes: Palettes<'w>,
+}
+
+impl<'w> Sprites<'w> {
+    pub fn is_loaded(&self) -> bool {
+        self.catalogs.get(&self.library.catalog).is_some()
+    }
+
+    pub fn def(&self, name: &str) -> Option<&SpriteDef> {
+        self.catalogs
+            .get(&self.library.catalog)
+            .and_then(|catalog| catalog.sprites.get(name))
+    }
+
+    pub fn sprite(&self, name: &str) -> TextureAtlasSprite {
+        match self.def(name) {
+            Some(def) => def.to_sprite(&self.palettes),
+            None => {
+                warn!("Sprite {name:?} is missing from the catalog");
+                TextureAtlasSprite::default()
+            }
+        }
+    }
+
+    /// Palette tint of the sprite, to keep it in sync with palette swaps.
+    pub fn palette_color(&self, name: &str) -> PaletteColor {
+        self.def(name)
+            .and_then(SpriteDef::palette_color)
+            .unwrap_or_default()
+    }
+
+    pub fn atlas(&self, name: &str) -> Handle<TextureAtlas> {
+        self.def(name)
+            .and_then(|def| self.library.atlases.get(&def.atlas))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn bundle(&self, name: &str, transform: Transform) -> SpriteSheetBundle {
+        SpriteSheetBundle {
+            texture_atlas: self.atlas(name),
+            sprite: self.sprite(name),
+            transform,
+            ..default()
+        }
+    }
+}
+
+/// Whether `events` contain a load or hot reload of `catalog`.
+pub fn catalog_changed(
+    events: &mut EventReader<AssetEvent<SpriteCatalog>>,
+    catalog: &Handle<SpriteCatalog>,
+) -> bool {
+    events.iter().any(|event| match event {
+        AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle == catalog,
+        AssetEvent::Removed { .. } => false,
+    })
+}
+
+pub fn build_sprite_atlases(
+    mut library: ResMut<SpriteLibrary>,
+    mut events: EventReader<AssetEvent<SpriteCatalog>>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+    catalogs: Res<Assets<SpriteCatalog>>,
+    asset_server: Res<AssetServer>,
+) {
+    if !catalog_changed(&mut events, &library.catalog) {
+        return;
+    }
+
+    let Some(catalog) = catalogs.get(&library.catalog) else {
+        return;
     };
+
+    for (name, def) in catalog.atlases.iter() {
+        let atlas = TextureAtlas::from_grid(
+            asset_server.load(&def.texture),
+            def.tile_size.into(),
+            def.columns,
+            def.rows,
+            def.padding.map(Vec2::from),
+            def.offset.map(Vec2::from),
+        );
+
+        match library
+            .atlases
+            .get(name)
+            .and_then(|handle| atlases.get_mut(handle))
+        {
+            Some(existing) => *existing = atlas,
+            None => {
+                let handle = atlases.add(atlas);
+                library.atlases.insert(name.clone(), handle);
+            }
+        }
+    }
+}
+
+pub fn apply_sprite_names(
+    mut commands: Commands,
+    mut named: Query<(
+        Entity,
+        Ref<SpriteName>,
+        &mut TextureAtlasSprite,
+        &mut Handle<TextureAtlas>,
+    )>,
+    mut events: EventReader<AssetEvent<SpriteCatalog>>,
+    library: Res<SpriteLibrary>,
+    sprites: Sprites,
+) {
+    let refresh_all = catalog_changed(&mut events, &library.catalog);
+    if !sprites.is_loaded() {
+        return;
+    }
+
+    for (entity, name, mut sprite, mut atlas) in named.iter_mut() {
+        if !refresh_all && !name.is_changed() {
+            continue;
+        }
+
+        *sprite = sprites.sprite(&name.0);
+        *atlas = sprites.atlas(&name.0);
+        commands
+            .entity(entity)
+            .insert(sprites.palette_color(&name.0));
+    }
 }